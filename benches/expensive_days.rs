@@ -0,0 +1,24 @@
+use advent_of_code_template::{runner::solve_day, utils::load_input, ProblemPart};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// benchmarks both parts of the days that dominate the runtime on the real inputs
+fn bench_expensive_days(c: &mut Criterion) {
+    let mut group = c.benchmark_group("expensive_days");
+    group.sample_size(10);
+
+    for day in [12, 15, 16, 17] {
+        let Ok(puzzle_input) = load_input(&format!("inputs/day_{day:02}.txt")) else {
+            eprintln!("skipping day {day}, missing input");
+            continue;
+        };
+        for part in [ProblemPart::One, ProblemPart::Two] {
+            group.bench_function(format!("day_{day:02}_part_{part}").to_lowercase(), |b| {
+                b.iter(|| solve_day(day, &puzzle_input, part.clone()).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_expensive_days);
+criterion_main!(benches);
@@ -0,0 +1,96 @@
+use std::process;
+
+use advent_of_code_template::runner::{
+    load_expected_answers, run_all, run_all_parallel, runs_to_json, verify_all, RunOutcome,
+};
+use clap::Parser;
+
+/// Solves every day and prints a summary table
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct RunAllArgs {
+    /// Solve the days concurrently
+    #[arg(long)]
+    parallel: bool,
+    /// Print the answers as JSON instead of the table
+    #[arg(long)]
+    json: bool,
+    /// Check the answers against the expected ones in the given JSON file
+    #[arg(long, value_name = "ANSWERS")]
+    verify: Option<String>,
+}
+
+fn main() {
+    let args = RunAllArgs::parse();
+
+    let input_path = |day: u8| format!("inputs/day_{day:02}.txt");
+
+    if let Some(answers_path) = args.verify {
+        let expected = load_expected_answers(&answers_path).unwrap_or_else(|error| {
+            eprintln!("{error}");
+            process::exit(2);
+        });
+        let errors = verify_all(&expected, input_path);
+        for error in errors.iter() {
+            println!("{error}");
+        }
+        println!(
+            "{} answers verified, {} wrong",
+            expected.len(),
+            errors.len()
+        );
+        if !errors.is_empty() {
+            process::exit(1);
+        }
+        return;
+    }
+    let runs = if args.parallel {
+        run_all_parallel(1..=17, input_path)
+    } else {
+        run_all(1..=17, input_path)
+    };
+
+    if args.json {
+        match runs_to_json(&runs) {
+            Ok(json) => println!("{json}"),
+            Err(error) => eprintln!("cannot serialize the answers: {error}"),
+        }
+        return;
+    }
+
+    println!(
+        "{:>3} | {:>4} | {:>20} | {:>12}",
+        "day", "part", "answer", "elapsed"
+    );
+    println!("{:-<3}-+-{:-<4}-+-{:-<20}-+-{:-<12}", "", "", "", "");
+    for run in runs {
+        match run.outcome {
+            RunOutcome::Solved { answer, elapsed } => {
+                let answer = answer.to_string();
+                // multi line answers, like the screen of day 10, are printed below the row
+                let mut lines = answer.lines();
+                let first_line = if answer.contains('\n') {
+                    ""
+                } else {
+                    lines.next().unwrap_or("")
+                };
+                println!(
+                    "{:>3} | {:>4} | {:>20} | {:>9.3} ms",
+                    run.day,
+                    run.part,
+                    first_line,
+                    elapsed.as_secs_f64() * 1000.0
+                );
+                for line in lines {
+                    println!("{:>3}   {:>4}   {line}", "", "");
+                }
+            }
+            RunOutcome::Failed(error) => {
+                println!("{:>3} | {:>4} | failed: {error}", run.day, run.part)
+            }
+            RunOutcome::Skipped(reason) => {
+                println!("{:>3} | {:>4} | skipped: {reason}", run.day, run.part)
+            }
+        }
+    }
+}
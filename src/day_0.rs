@@ -12,15 +12,19 @@ pub fn solve(_puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error
             info!("Start solving part 1");
             let start = Instant::now();
             solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
+            let duration = start.elapsed().as_millis();
+            info!("Solved part 1 in {duration} milli seconds.");
         }
         ProblemPart::Two => {
             info!("Start solving part 2");
             let start = Instant::now();
             solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
+            let duration = start.elapsed().as_millis();
+            info!("Solved part 2 in {duration} milli seconds.");
+        }
+        ProblemPart::Both => {
+            solve(_puzzle_input, ProblemPart::One)?;
+            solve(_puzzle_input, ProblemPart::Two)?;
         }
     };
     Ok(())
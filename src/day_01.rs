@@ -1,72 +1,46 @@
-use std::{error::Error, fs::File, io::Read, time::Instant};
+use std::error::Error;
 
-use log::info;
+use crate::Answer;
 
-use crate::ProblemPart;
+solve_day!(solve_pt1, solve_pt2);
 
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
-
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
-        }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
-}
-
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let mut max_calories = 0;
+/// returns the calories carried by each elf, the groups of items are separated
+/// by one or more blank lines
+fn elves_calories(puzzle_input: &str) -> Result<Vec<i32>, Box<dyn Error>> {
+    let mut calories: Vec<i32> = Vec::new();
 
-    let mut current_calories = 0;
-    for line in puzzle_input.lines() {
+    let mut current_calories: Option<i32> = None;
+    for line in puzzle_input.lines().map(|line| line.trim()) {
         if line.is_empty() {
-            max_calories = max_calories.max(current_calories);
-            current_calories = 0;
+            // a run of blank lines closes a single group
+            calories.extend(current_calories.take());
         } else {
-            current_calories += line.parse::<i32>().unwrap();
+            *current_calories.get_or_insert(0) += line.parse::<i32>()?;
         }
     }
-    Ok(max_calories.to_string())
+    calories.extend(current_calories);
+    Ok(calories)
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let mut calories: Vec<i32> = Vec::new();
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let calories = elves_calories(&puzzle_input)?;
+    Ok(Answer::from(calories.into_iter().max().unwrap_or(0)))
+}
 
-    let mut current_calories = 0;
-    for block in puzzle_input.split("\n\n") {
-        for line in block.lines() {
-            current_calories += line.parse::<i32>().unwrap();
-        }
-        calories.push(current_calories);
-        current_calories = 0;
-    }
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let mut calories = elves_calories(&puzzle_input)?;
     calories.sort();
     calories.reverse();
-    Ok(calories.iter().take(3).sum::<i32>().to_string())
+    Ok(Answer::from(calories.iter().take(3).sum::<i32>()))
 }
 
 #[cfg(test)]
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use crate::{Answer, ProblemPart};
+
+    use super::{elves_calories, solve_pt1, solve_pt2, solve_str};
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -75,7 +49,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!(String::from("24000"), result);
+        assert_eq!(Answer::Int(24000), result);
 
         Ok(())
     }
@@ -87,7 +61,53 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!(String::from("45000"), result);
+        assert_eq!(Answer::Int(45000), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_str() -> Result<(), Box<dyn Error>> {
+        let puzzle_input = "1000\n2000\n\n4000\n\n5000\n6000\n\n7000\n";
+
+        assert_eq!(
+            Answer::Int(11000),
+            solve_str(puzzle_input, ProblemPart::One)?
+        );
+        assert_eq!(
+            Answer::Int(22000),
+            solve_str(puzzle_input, ProblemPart::Two)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_both() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_01_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let result = solve_str(&puzzle_input, ProblemPart::Both)?;
+
+        assert_eq!(
+            Answer::Both(Box::new(Answer::Int(24000)), Box::new(Answer::Int(45000))),
+            result
+        );
+        assert_eq!("24000, 45000", result.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiple_blank_lines() -> Result<(), Box<dyn Error>> {
+        let puzzle_input = "\n1000\n2000\n\n\n4000\n\n\n\n5000\n6000\n\n7000\n\n";
+
+        assert_eq!(vec![3000, 4000, 11000, 7000], elves_calories(puzzle_input)?);
+        assert_eq!(Answer::Int(11000), solve_pt1(puzzle_input.to_string())?);
+        assert_eq!(Answer::Int(22000), solve_pt2(puzzle_input.to_string())?);
+
+        // the last group is counted even without the final new line
+        assert_eq!(Answer::Int(9000), solve_pt1("1000\n\n9000".to_string())?);
 
         Ok(())
     }
@@ -1,63 +1,64 @@
-use std::{error::Error, fs::File, io::Read, str::FromStr, time::Instant};
+use std::{error::Error, str::FromStr};
 
-use log::info;
 use strum_macros::EnumString;
 
-use crate::ProblemPart;
-
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
-
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
-        }
+use crate::Answer;
+
+solve_day!(solve_pt1, solve_pt2);
+
+/// points of the round when the second column is my play
+fn play_points(opponent_play: &Play, my_play: &Play) -> i32 {
+    let outcome_points = match my_play.cmp(opponent_play) {
+        std::cmp::Ordering::Equal => 3,
+        std::cmp::Ordering::Greater => 6,
+        std::cmp::Ordering::Less => 0,
     };
-    info!("Problem solution is {}", result);
-    Ok(())
+    outcome_points + my_play.get_type_point()
+}
+
+/// points of the round when the second column is the result of the round
+fn result_points(opponent_play: &Play, match_result: &MatchResult) -> i32 {
+    let my_play = match_result.get_play_type(&opponent_play.get_type());
+    my_play.get_type_point() + match_result.get_points()
 }
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
     let mut total_points = 0;
     for line in puzzle_input.lines() {
         let mut line_split = line.split_whitespace();
         let opponent_play = Play::from_str(line_split.next().unwrap()).unwrap();
         let my_play = Play::from_str(line_split.next().unwrap()).unwrap();
-        match my_play.cmp(&opponent_play) {
-            std::cmp::Ordering::Equal => total_points += 3,
-            std::cmp::Ordering::Greater => total_points += 6,
-            _ => {}
-        }
-        total_points += my_play.get_type_point();
+        total_points += play_points(&opponent_play, &my_play);
     }
-    Ok(total_points.to_string())
+    Ok(Answer::from(total_points))
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
     let mut total_points = 0;
     for line in puzzle_input.lines() {
         let mut line_split = line.split_whitespace();
         let opponent_play = Play::from_str(line_split.next().unwrap()).unwrap();
         let match_result = MatchResult::from_str(line_split.next().unwrap()).unwrap();
-        let my_play = match_result.get_play_type(&opponent_play.get_type());
-        total_points += my_play.get_type_point() + match_result.get_points();
+        total_points += result_points(&opponent_play, &match_result);
+    }
+    Ok(Answer::from(total_points))
+}
+
+/// computes the total points of both parts parsing each line once,
+/// the second column is read both as my play and as the result of the round
+pub fn solve_both(puzzle_input: &str) -> (i32, i32) {
+    let mut total_points = (0, 0);
+    for line in puzzle_input.lines() {
+        let mut line_split = line.split_whitespace();
+        let opponent_play = Play::from_str(line_split.next().unwrap()).unwrap();
+        let second_column = line_split.next().unwrap();
+        let my_play = Play::from_str(second_column).unwrap();
+        let match_result = MatchResult::from_str(second_column).unwrap();
+
+        total_points.0 += play_points(&opponent_play, &my_play);
+        total_points.1 += result_points(&opponent_play, &match_result);
     }
-    Ok(total_points.to_string())
+    total_points
 }
 
 #[derive(Debug, PartialEq, Eq, EnumString)]
@@ -173,7 +174,9 @@ impl Ord for Play {
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use crate::Answer;
+
+    use super::{solve_both, solve_pt1, solve_pt2};
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -182,7 +185,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!("15", result);
+        assert_eq!(Answer::Int(15), result);
 
         Ok(())
     }
@@ -194,7 +197,21 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!("12", result);
+        assert_eq!(Answer::Int(12), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_both() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_02_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let (pt1, pt2) = solve_both(&puzzle_input);
+
+        assert_eq!((15, 12), (pt1, pt2));
+        assert_eq!(Answer::from(pt1), solve_pt1(puzzle_input.clone())?);
+        assert_eq!(Answer::from(pt2), solve_pt2(puzzle_input)?);
 
         Ok(())
     }
@@ -1,40 +1,27 @@
-use std::{collections::HashSet, error::Error, fs::File, io::Read, time::Instant};
-
-use log::info;
-
-use crate::ProblemPart;
-
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
-
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
-        }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
+use std::{collections::HashSet, error::Error};
+
+use crate::Answer;
+
+solve_day!(solve_pt1, solve_pt2);
+
+/// items in priority order, from 1 to 52
+pub const DEFAULT_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// returns the priority of the item as its position in the alphabet, starting from 1
+pub fn priority_with(item: char, alphabet: &str) -> Option<u8> {
+    alphabet
+        .chars()
+        .position(|c| c == item)
+        .and_then(|position| u8::try_from(position + 1).ok())
 }
 
-const LOWER_OFFSET: u8 = b'a';
-const HIGHER_OFFSET: u8 = b'A';
+fn priority(item: char) -> Result<i32, Box<dyn Error>> {
+    let priority =
+        priority_with(item, DEFAULT_ALPHABET).ok_or(format!("{item} is not a valid item"))?;
+    Ok(priority as i32)
+}
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
     let mut priority_sum: i32 = 0;
     for line in puzzle_input.lines() {
         let first_compartment = line.chars().take(line.len() / 2).collect::<HashSet<char>>();
@@ -42,17 +29,12 @@ fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
         let item = first_compartment
             .intersection(&second_compartment)
             .collect::<Vec<&char>>()[0];
-        let offset = if item.is_ascii_lowercase() {
-            LOWER_OFFSET - 1
-        } else {
-            HIGHER_OFFSET - 27
-        };
-        priority_sum += (*item as u8 - offset) as i32;
+        priority_sum += priority(*item)?;
     }
-    Ok(priority_sum.to_string())
+    Ok(Answer::from(priority_sum))
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
     let mut priority_sum: i32 = 0;
     for group in puzzle_input.lines().collect::<Vec<&str>>().chunks(3) {
         let badge = group
@@ -64,21 +46,38 @@ fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
             .next()
             .unwrap();
 
-        let offset = if badge.is_ascii_lowercase() {
-            LOWER_OFFSET - 1
-        } else {
-            HIGHER_OFFSET - 27
-        };
-        priority_sum += (badge as u8 - offset) as i32;
+        priority_sum += priority(badge)?;
+    }
+    Ok(Answer::from(priority_sum))
+}
+
+/// like part one but items are case insensitive with priorities from 1 to 26,
+/// for inputs whose case was lost.
+///
+/// When ignoring the case merges different items, each item type
+/// in both compartments adds its priority once
+pub fn solve_pt1_ci(puzzle_input: &str) -> i32 {
+    let mut priority_sum: i32 = 0;
+    for line in puzzle_input.lines() {
+        let line = line.to_ascii_lowercase();
+        let first_compartment = line.chars().take(line.len() / 2).collect::<HashSet<char>>();
+        let second_compartment = line.chars().skip(line.len() / 2).collect::<HashSet<char>>();
+        priority_sum += first_compartment
+            .intersection(&second_compartment)
+            .filter_map(|item| priority_with(*item, DEFAULT_ALPHABET))
+            .map(|priority| priority as i32)
+            .sum::<i32>();
     }
-    Ok(priority_sum.to_string())
+    priority_sum
 }
 
 #[cfg(test)]
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use crate::Answer;
+
+    use super::{priority_with, solve_pt1, solve_pt1_ci, solve_pt2, DEFAULT_ALPHABET};
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -87,7 +86,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!(String::from("157"), result);
+        assert_eq!(Answer::Int(157), result);
 
         Ok(())
     }
@@ -99,8 +98,42 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!(String::from("70"), result);
+        assert_eq!(Answer::Int(70), result);
 
         Ok(())
     }
+
+    #[test]
+    fn test_pt1_ci() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_03_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        // the duplicated items of the first rucksack are p and P, both with priority 16
+        assert_eq!(16, solve_pt1_ci("vJrwpWtwJgWrhcsFMMfFFhFp\n"));
+        assert_eq!(16, solve_pt1_ci("VJRWPWTWJGWRHCSFMMFFFHFP\n"));
+
+        let lowercased = puzzle_input.to_lowercase();
+        assert_eq!(204, solve_pt1_ci(&lowercased));
+        assert_eq!(solve_pt1_ci(&puzzle_input), solve_pt1_ci(&lowercased));
+        // the default behavior is unchanged
+        assert_eq!(Answer::Int(157), solve_pt1(puzzle_input)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_priority_with() {
+        assert_eq!(Some(1), priority_with('a', DEFAULT_ALPHABET));
+        assert_eq!(Some(26), priority_with('z', DEFAULT_ALPHABET));
+        assert_eq!(Some(27), priority_with('A', DEFAULT_ALPHABET));
+        assert_eq!(Some(52), priority_with('Z', DEFAULT_ALPHABET));
+        assert_eq!(None, priority_with('1', DEFAULT_ALPHABET));
+
+        let alphabet = "1234567890#$%&";
+        assert_eq!(Some(1), priority_with('1', alphabet));
+        assert_eq!(Some(10), priority_with('0', alphabet));
+        assert_eq!(Some(14), priority_with('&', alphabet));
+        assert_eq!(None, priority_with('a', alphabet));
+    }
 }
@@ -1,35 +1,8 @@
-use std::{error::Error, fs::File, io::Read, time::Instant};
+use std::error::Error;
 
-use log::info;
+use crate::Answer;
 
-use crate::ProblemPart;
-
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
-
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
-        }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
-}
+solve_day!(solve_pt1, solve_pt2);
 
 fn build_range(input: &str) -> (i32, i32) {
     let range = input
@@ -39,6 +12,20 @@ fn build_range(input: &str) -> (i32, i32) {
     (range[0], range[1])
 }
 
+/// like build_range but a missing bound is replaced by the given one,
+/// so 5- is 5..=hi and -5 is lo..=5
+pub fn build_range_bounded(input: &str, lo: i32, hi: i32) -> (i32, i32) {
+    let (start, end) = input.split_once('-').unwrap();
+    let parse_or = |bound: &str, default: i32| {
+        if bound.is_empty() {
+            default
+        } else {
+            bound.parse::<i32>().unwrap()
+        }
+    };
+    (parse_or(start, lo), parse_or(end, hi))
+}
+
 fn is_fully_contained(range: (i32, i32), other: (i32, i32)) -> bool {
     (range.0 >= other.0) & (range.1 <= other.1)
 }
@@ -47,7 +34,7 @@ fn overlaps(range: (i32, i32), other: (i32, i32)) -> bool {
     !((range.1 < other.0) | (range.0 > other.1))
 }
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
     let mut result = 0;
     for pair in puzzle_input.lines() {
         let (first, second) = {
@@ -62,10 +49,10 @@ fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
         }
     }
 
-    Ok(result.to_string())
+    Ok(Answer::from(result))
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
     let mut result = 0;
     for pair in puzzle_input.lines() {
         let (first, second) = {
@@ -80,14 +67,38 @@ fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
         }
     }
 
-    Ok(result.to_string())
+    Ok(Answer::from(result))
+}
+
+/// returns the number of distinct sections assigned to at least one elf
+pub fn total_sections_covered(puzzle_input: &str) -> usize {
+    let mut ranges: Vec<(i32, i32)> = puzzle_input
+        .lines()
+        .flat_map(|pair| pair.split(',').map(build_range))
+        .collect();
+    ranges.sort();
+
+    let mut covered = 0;
+    // first section not counted yet, since the ranges are sorted by start
+    // the overlapping part of each range has already been counted
+    let mut next_section = i32::MIN;
+    for (start, end) in ranges {
+        let start = start.max(next_section);
+        if start <= end {
+            covered += (end - start + 1) as usize;
+            next_section = end + 1;
+        }
+    }
+    covered
 }
 
 #[cfg(test)]
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use crate::Answer;
+
+    use super::{build_range_bounded, solve_pt1, solve_pt2, total_sections_covered};
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -96,7 +107,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!(String::from("2"), result);
+        assert_eq!(Answer::Int(2), result);
 
         Ok(())
     }
@@ -108,8 +119,30 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!(String::from("4"), result);
+        assert_eq!(Answer::Int(4), result);
 
         Ok(())
     }
+
+    #[test]
+    fn test_total_sections_covered() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_04_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        // every section from 2 to 9
+        assert_eq!(8, total_sections_covered(&puzzle_input));
+        assert_eq!(6, total_sections_covered("1-2,2-3\n7-9,8-8\n"));
+        assert_eq!(0, total_sections_covered(""));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_range_bounded() {
+        assert_eq!((5, 99), build_range_bounded("5-", 1, 99));
+        assert_eq!((1, 5), build_range_bounded("-5", 1, 99));
+        assert_eq!((2, 4), build_range_bounded("2-4", 1, 99));
+        assert_eq!((1, 99), build_range_bounded("-", 1, 99));
+    }
 }
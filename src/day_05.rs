@@ -1,42 +1,15 @@
 use std::{
     collections::{HashMap, VecDeque},
     error::Error,
-    fs::File,
-    io::Read,
-    time::Instant,
 };
 
-use log::info;
 use regex::Regex;
 
-use crate::ProblemPart;
-
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
-
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
-        }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
-}
+use crate::{error::AocError, Answer};
+
+solve_day!(solve_pt1, solve_pt2);
+
+type Stacks = HashMap<i32, VecDeque<char>>;
 
 struct Move {
     qt: i32,
@@ -62,10 +35,17 @@ impl Move {
     }
 }
 
-fn parse_input(puzzle_input: String) -> (HashMap<i32, VecDeque<char>>, Vec<Move>) {
+fn parse_input(puzzle_input: String) -> Result<(Stacks, Vec<Move>), AocError> {
     let mut split = puzzle_input.split("\n\n");
-    let stacks_to_parse = split.next().unwrap();
-    let moves_to_parse = split.next().unwrap();
+    let stacks_to_parse = split.next().unwrap_or_default();
+    let stacks_lines = stacks_to_parse.lines().count();
+    let moves_to_parse = split.next().ok_or_else(|| {
+        AocError::parse(
+            5,
+            stacks_lines,
+            "missing the empty line between the stacks and the moves",
+        )
+    })?;
 
     let mut stacks = HashMap::new();
     for line in stacks_to_parse.lines() {
@@ -90,23 +70,41 @@ fn parse_input(puzzle_input: String) -> (HashMap<i32, VecDeque<char>>, Vec<Move>
 
     let mut moves = Vec::new();
     let re = Regex::new(r"\b\d+\b").unwrap();
-    for move_to_parse in moves_to_parse.lines() {
-        let matches: Vec<i32> = re
+    for (i, move_to_parse) in moves_to_parse.lines().enumerate() {
+        // moves come after the stacks and the empty line
+        let line = stacks_lines + 2 + i;
+        let matches = re
             .find_iter(move_to_parse)
-            .map(|m| m.as_str().parse::<i32>().unwrap())
-            .collect();
-
-        moves.push(Move {
-            qt: matches[0],
-            from: matches[1],
-            to: matches[2],
-        });
+            .map(|m| {
+                m.as_str()
+                    .parse::<i32>()
+                    .map_err(|error| AocError::parse(5, line, format!("{}: {error}", m.as_str())))
+            })
+            .collect::<Result<Vec<i32>, AocError>>()?;
+
+        let [qt, from, to] = matches[..] else {
+            return Err(AocError::parse(
+                5,
+                line,
+                format!("expected quantity, origin and destination in '{move_to_parse}'"),
+            ));
+        };
+        for stack_id in [from, to] {
+            if !stacks.contains_key(&stack_id) {
+                return Err(AocError::parse(
+                    5,
+                    line,
+                    format!("stack {stack_id} does not exist"),
+                ));
+            }
+        }
+        moves.push(Move { qt, from, to });
     }
-    (stacks, moves)
+    Ok((stacks, moves))
 }
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let (mut stacks, moves) = parse_input(puzzle_input);
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let (mut stacks, moves) = parse_input(puzzle_input)?;
 
     for move_to_apply in moves {
         move_to_apply.apply(&mut stacks);
@@ -116,11 +114,11 @@ fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
     for i in 1..=*stacks.keys().max().unwrap() {
         result.push(stacks.get_mut(&i).unwrap().pop_front().unwrap());
     }
-    Ok(result)
+    Ok(Answer::Text(result))
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let (mut stacks, moves) = parse_input(puzzle_input);
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let (mut stacks, moves) = parse_input(puzzle_input)?;
 
     for move_to_apply in moves {
         move_to_apply.apply_9001(&mut stacks);
@@ -130,14 +128,16 @@ fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
     for i in 1..=*stacks.keys().max().unwrap() {
         result.push(stacks.get_mut(&i).unwrap().pop_front().unwrap());
     }
-    Ok(result)
+    Ok(Answer::Text(result))
 }
 
 #[cfg(test)]
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use crate::{error::AocError, Answer};
+
+    use super::{parse_input, solve_pt1, solve_pt2};
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -146,7 +146,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!("CMZ".to_string(), result);
+        assert_eq!(Answer::Text("CMZ".to_string()), result);
 
         Ok(())
     }
@@ -158,8 +158,60 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!("MCD".to_string(), result);
+        assert_eq!(Answer::Text("MCD".to_string()), result);
 
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_input() {
+        let stacks = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n";
+
+        let error = parse_input(format!("{stacks}\nmove 1 from 2 to 1\nmove 1 from 2\n"))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error,
+            AocError::Parse {
+                day: 5,
+                line: 7,
+                ..
+            }
+        ));
+
+        let error = parse_input(format!("{stacks}\nmove 1 from 4 to 1\n"))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error,
+            AocError::Parse {
+                day: 5,
+                line: 6,
+                ..
+            }
+        ));
+        assert!(error.to_string().contains("stack 4 does not exist"));
+
+        let error = parse_input(format!("{stacks}\nmove 99999999999 from 2 to 1\n"))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error,
+            AocError::Parse {
+                day: 5,
+                line: 6,
+                ..
+            }
+        ));
+
+        let error = parse_input(stacks.to_string()).err().unwrap();
+        assert!(matches!(
+            error,
+            AocError::Parse {
+                day: 5,
+                line: 4,
+                ..
+            }
+        ));
+    }
 }
@@ -1,87 +1,46 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    error::Error,
-    fs::File,
-    io::Read,
-    time::Instant,
-};
-
-use log::info;
-
-use crate::ProblemPart;
-
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
-
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
-        }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
-}
+use std::{collections::HashSet, error::Error};
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let min_len = 4;
-    let mut window: VecDeque<char> = puzzle_input.chars().take(min_len).collect();
-    if window.iter().collect::<HashSet<&char>>().len() == min_len {
-        return Ok("4".to_string());
-    }
+use crate::Answer;
 
-    let mut result = 0;
-    for (i, c) in puzzle_input.chars().skip(min_len).enumerate() {
-        window.pop_front();
-        window.push_back(c);
-        if window.iter().collect::<HashSet<&char>>().len() == min_len {
-            result = i + min_len + 1;
-            break;
-        }
-    }
+solve_day!(solve_pt1, solve_pt2);
 
-    Ok(result.to_string())
+/// returns the number of characters processed when the first window of the given
+/// size satisfying the predicate is found, that is the position after its last character
+///
+/// an empty window cannot contain a marker, hence it is never found
+pub fn find_window<F: Fn(&[char]) -> bool>(input: &str, window: usize, pred: F) -> Option<usize> {
+    if window == 0 {
+        return None;
+    }
+    let chars: Vec<char> = input.chars().collect();
+    chars
+        .windows(window)
+        .position(pred)
+        .map(|start| start + window)
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let min_len = 14;
-    let mut window: VecDeque<char> = puzzle_input.chars().take(min_len).collect();
-    if window.iter().collect::<HashSet<&char>>().len() == min_len {
-        return Ok("4".to_string());
-    }
+fn all_distinct(window: &[char]) -> bool {
+    window.iter().collect::<HashSet<&char>>().len() == window.len()
+}
 
-    let mut result = 0;
-    for (i, c) in puzzle_input.chars().skip(min_len).enumerate() {
-        window.pop_front();
-        window.push_back(c);
-        if window.iter().collect::<HashSet<&char>>().len() == min_len {
-            result = i + min_len + 1;
-            break;
-        }
-    }
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let result = find_window(&puzzle_input, 4, all_distinct).ok_or("start of packet not found")?;
+    Ok(Answer::from(result))
+}
 
-    Ok(result.to_string())
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let result =
+        find_window(&puzzle_input, 14, all_distinct).ok_or("start of message not found")?;
+    Ok(Answer::from(result))
 }
 
 #[cfg(test)]
 mod test {
-    use std::{error::Error, fs::File, io::Read};
+    use std::{collections::HashSet, error::Error, fs::File, io::Read};
+
+    use crate::Answer;
 
-    use super::{solve_pt1, solve_pt2};
+    use super::{find_window, solve_pt1, solve_pt2};
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -91,7 +50,7 @@ mod test {
 
         for (seq, solution) in puzzle_input.lines().zip(vec![7, 5, 6, 10, 11]) {
             let result = solve_pt1(seq.to_string())?;
-            assert_eq!(solution.to_string(), result);
+            assert_eq!(Answer::Int(solution), result);
         }
 
         Ok(())
@@ -104,9 +63,36 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         for (seq, solution) in puzzle_input.lines().zip(vec![19, 23, 23, 29, 26]) {
             let result = solve_pt2(seq.to_string())?;
-            assert_eq!(solution.to_string(), result);
+            assert_eq!(Answer::Int(solution), result);
         }
 
         Ok(())
     }
+
+    #[test]
+    fn test_find_window() {
+        // exactly two equal letters in the window
+        let one_repetition =
+            |window: &[char]| window.iter().collect::<HashSet<&char>>().len() == window.len() - 1;
+        assert_eq!(Some(6), find_window("abcdeefg", 4, one_repetition));
+        assert_eq!(Some(3), find_window("aabcd", 3, one_repetition));
+
+        let same_ends = |window: &[char]| window.first() == window.last();
+        assert_eq!(Some(6), find_window("xyzwvzq", 4, same_ends));
+        assert_eq!(None, find_window("abcdef", 4, same_ends));
+        assert_eq!(None, find_window("ab", 4, |_| true));
+        assert_eq!(None, find_window("abcd", 0, |_| true));
+    }
+
+    #[test]
+    fn test_marker_at_start() -> Result<(), Box<dyn Error>> {
+        assert_eq!(Answer::Int(4), solve_pt1("abcdaaaa".to_string())?);
+        assert_eq!(
+            Answer::Int(14),
+            solve_pt2("abcdefghijklmnaaaa".to_string())?
+        );
+        assert!(solve_pt1("aaaaaa".to_string()).is_err());
+
+        Ok(())
+    }
 }
@@ -1,37 +1,15 @@
 use std::{
-    cell::RefCell, collections::HashMap, error::Error, fs::File, io::Read, rc::Rc, time::Instant,
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    rc::Rc,
 };
 
-use log::{debug, info};
+use log::debug;
 
-use crate::ProblemPart;
+use crate::Answer;
 
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
-
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
-        }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
-}
+solve_day!(solve_pt1, solve_pt2);
 
 /// Filesystem enum has two variants:
 /// - directory that has a name
@@ -111,6 +89,26 @@ impl TreeArena {
         }
     }
 
+    /// removes the node and, if it is a directory, all its content
+    fn remove_node(&mut self, node_id: i32) {
+        let Some(node) = self.map.remove(&node_id) else {
+            return;
+        };
+        let node = node.borrow();
+        for child in node.children.iter() {
+            self.remove_node(*child);
+        }
+        if let Some(parent) = node.parent.and_then(|parent_id| self.get_node(parent_id)) {
+            parent
+                .borrow_mut()
+                .children
+                .retain(|&child| child != node_id);
+        }
+        if self.root == Some(node_id) {
+            self.root = None;
+        }
+    }
+
     fn get_root(&self) -> Option<Rc<RefCell<Node>>> {
         self.root.map(|node_id| self.get_node(node_id).unwrap())
     }
@@ -124,13 +122,13 @@ impl TreeArena {
         }
         match &node.node_type {
             NodeType::Directory(name) => {
-                println!("{spaces}- {name} (dir)");
+                debug!("{spaces}- {name} (dir)");
                 for child in node.children.iter() {
                     self.print(*child);
                 }
             }
             NodeType::File(name, size) => {
-                println!("{spaces}- ({name}, size={size})");
+                debug!("{spaces}- ({name}, size={size})");
             }
         }
     }
@@ -152,6 +150,50 @@ impl TreeArena {
         size
     }
 
+    fn name(&self, node_id: i32) -> String {
+        match &self.get_node(node_id).unwrap().borrow().node_type {
+            NodeType::Directory(name) | NodeType::File(name, _) => name.clone(),
+        }
+    }
+
+    /// returns the node at the absolute path, like /a/e, starting from the root
+    fn resolve_path(&self, path: &str) -> Option<i32> {
+        let mut node_id = self.root?;
+        for name in path.split('/').filter(|name| !name.is_empty()) {
+            let node = self.get_node(node_id)?;
+            node_id = node
+                .borrow()
+                .children
+                .iter()
+                .copied()
+                .find(|&child| self.name(child) == name)?;
+        }
+        Some(node_id)
+    }
+
+    /// returns the total size of the directory, or of the file, at the absolute path
+    fn size_at_path(&self, path: &str) -> Option<i32> {
+        self.resolve_path(path).map(|node_id| self.size(node_id))
+    }
+
+    /// returns the used and the free space of a disk with the given capacity
+    fn disk_usage(&self, total: i32) -> (i32, i32) {
+        let used = self.size_at_path("/").unwrap_or(0);
+        (used, total - used)
+    }
+
+    /// returns the number of directories at each depth of the tree, the root is at depth 0
+    fn depth_histogram(&self) -> BTreeMap<i32, usize> {
+        let mut histogram: BTreeMap<i32, usize> = BTreeMap::new();
+        for node in self.map.values() {
+            let node = node.borrow();
+            if let NodeType::Directory(_) = node.node_type {
+                *histogram.entry(node.depth).or_insert(0) += 1;
+            }
+        }
+        histogram
+    }
+
     fn is_directory(&self, node_id: i32) -> bool {
         matches!(
             self.get_node(node_id).unwrap().borrow().node_type,
@@ -189,6 +231,18 @@ fn parse_input(puzzle_input: String) -> TreeArena {
                     .unwrap();
                 current_node = arena.get_node(current_node_id).unwrap();
             }
+        } else if let Some(name) = line.strip_prefix("$ rm ") {
+            ls_output = false;
+            let child = current_node
+                .borrow()
+                .children
+                .iter()
+                .copied()
+                .find(|&child| arena.name(child) == name);
+            match child {
+                Some(child) => arena.remove_node(child),
+                None => debug!("Cannot remove {name}, it does not exist"),
+            }
         } else if ls_output {
             if !line.starts_with("dir") {
                 let file_size = line
@@ -210,44 +264,48 @@ fn parse_input(puzzle_input: String) -> TreeArena {
     arena
 }
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
     let arena = parse_input(puzzle_input);
     arena.print(arena.get_root().unwrap().borrow().id);
+    debug!("Directories per depth {:?}", arena.depth_histogram());
     let size_th = 100000;
     let mut result = 0;
-    for node_id in 1..=arena.global_counter {
+    // removed nodes are not in the arena anymore
+    for &node_id in arena.map.keys() {
         let size = arena.size(node_id);
         debug!("Size is {size}");
         if (size <= size_th) & arena.is_directory(node_id) {
             result += size;
         }
     }
-    Ok(result.to_string())
+    Ok(Answer::from(result))
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
     let arena = parse_input(puzzle_input);
     arena.print(arena.get_root().unwrap().borrow().id);
     let required_space = 30000000;
     let total_disk_space = 70000000;
-    let available_space = total_disk_space - arena.size(arena.get_root().unwrap().borrow().id);
+    let (_, available_space) = arena.disk_usage(total_disk_space);
     let space_to_free = required_space - available_space;
     let mut candidates_to_delete: Vec<i32> = Vec::new();
-    for node_id in 1..=arena.global_counter {
+    for &node_id in arena.map.keys() {
         let size = arena.size(node_id);
         if (size >= space_to_free) & arena.is_directory(node_id) {
             candidates_to_delete.push(size)
         }
     }
-    println!("candiates_to_delete \n{:?}", candidates_to_delete);
-    Ok(candidates_to_delete.iter().min().unwrap().to_string())
+    debug!("candiates_to_delete \n{:?}", candidates_to_delete);
+    Ok(Answer::from(*candidates_to_delete.iter().min().unwrap()))
 }
 
 #[cfg(test)]
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use crate::Answer;
+
+    use super::{parse_input, solve_pt1, solve_pt2};
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -256,7 +314,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!("95437".to_string(), result);
+        assert_eq!(Answer::Int(95437), result);
 
         Ok(())
     }
@@ -268,7 +326,85 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!("24933642".to_string(), result);
+        assert_eq!(Answer::Int(24933642), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_size_at_path() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_07_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let arena = parse_input(puzzle_input);
+
+        assert_eq!(Some(94853), arena.size_at_path("/a"));
+        assert_eq!(Some(584), arena.size_at_path("/a/e"));
+        assert_eq!(Some(24933642), arena.size_at_path("/d/"));
+        assert_eq!(Some(48381165), arena.size_at_path("/"));
+        // a file is sized as well
+        assert_eq!(Some(14848514), arena.size_at_path("/b.txt"));
+        assert_eq!(None, arena.size_at_path("/a/x"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rm() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_07_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        if !puzzle_input.ends_with('\n') {
+            puzzle_input.push('\n');
+        }
+
+        // the directory a is removed with its content
+        let removed_directory = format!("{puzzle_input}$ cd ..\n$ rm a\n");
+        let arena = parse_input(removed_directory.clone());
+
+        assert_eq!(Some(48381165 - 94853), arena.size_at_path("/"));
+        assert_eq!(None, arena.size_at_path("/a"));
+        assert_eq!(None, arena.size_at_path("/a/e"));
+        // a and e were the only small directories
+        assert_eq!(Answer::Int(0), solve_pt1(removed_directory)?);
+
+        let removed_file = format!("{puzzle_input}$ rm k\n$ rm missing\n");
+        let arena = parse_input(removed_file);
+
+        assert_eq!(Some(24933642 - 7214296), arena.size_at_path("/d"));
+        assert_eq!(Some(48381165 - 7214296), arena.size_at_path("/"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_usage() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_07_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let arena = parse_input(puzzle_input);
+        let (used, free) = arena.disk_usage(70000000);
+
+        assert_eq!(48381165, used);
+        assert_eq!(21618835, free);
+        assert_eq!(70000000, used + free);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_depth_histogram() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_07_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let arena = parse_input(puzzle_input);
+        let histogram = arena.depth_histogram();
+
+        // the root, then a and d, then e
+        assert_eq!(Some(&1), histogram.get(&0));
+        assert_eq!(Some(&2), histogram.get(&1));
+        assert_eq!(Some(&1), histogram.get(&2));
+        assert_eq!(3, histogram.len());
 
         Ok(())
     }
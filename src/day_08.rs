@@ -1,36 +1,10 @@
-use std::{collections::HashSet, error::Error, fs::File, io::Read, time::Instant};
+use std::{collections::HashSet, error::Error};
 
-use log::info;
 use ndarray::{s, Array2, ArrayView2};
 
-use crate::ProblemPart;
-
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
-
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
-        }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
-}
+use crate::Answer;
+
+solve_day!(solve_pt1, solve_pt2);
 
 fn parse_input(puzzle_input: String) -> Array2<i32> {
     let mut matrix = Array2::zeros((
@@ -45,23 +19,69 @@ fn parse_input(puzzle_input: String) -> Array2<i32> {
     matrix
 }
 
-fn find_visible_trees(matrix: ArrayView2<i32>) -> HashSet<(usize, usize)> {
-    let mut visible_trees: HashSet<(usize, usize)> = HashSet::new();
+/// parses a grid whose heights are integers separated by
+/// whitespaces or commas, so that heights greater than 9 are allowed
+///
+/// blank lines are skipped and all the rows must have the same length,
+/// an input without heights is an error
+pub fn parse_input_multi(puzzle_input: &str) -> Result<Array2<i32>, Box<dyn Error>> {
+    let mut heights: Vec<i32> = Vec::new();
+    let mut shape = (0, 0);
+    for (i, line) in puzzle_input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row = line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|el| !el.is_empty())
+            .map(|el| {
+                el.parse::<i32>()
+                    .map_err(|error| format!("line {}: invalid height '{el}': {error}", i + 1))
+            })
+            .collect::<Result<Vec<i32>, String>>()?;
+        if shape.0 == 0 {
+            shape.1 = row.len();
+        } else if row.len() != shape.1 {
+            return Err(format!(
+                "line {}: expected {} heights, found {}",
+                i + 1,
+                shape.1,
+                row.len()
+            )
+            .into());
+        }
+        heights.extend(row);
+        shape.0 += 1;
+    }
+    if shape.0 == 0 || shape.1 == 0 {
+        return Err("empty grid".into());
+    }
+    Ok(Array2::from_shape_vec(shape, heights)?)
+}
+
+/// returns a boolean grid with the same shape of the matrix where
+/// a cell is true if the tree is visible from one of the four cardinal directions
+pub fn visibility_grid(matrix: ArrayView2<i32>) -> Array2<bool> {
+    let mut visible_trees: Array2<bool> = Array2::from_elem(matrix.raw_dim(), false);
+    if matrix.is_empty() {
+        // there are no trees, hence no edges to look from
+        return visible_trees;
+    }
     for r in 0..matrix.shape()[0] {
-        visible_trees.insert((r, 0));
-        visible_trees.insert((r, matrix.shape()[1] - 1));
+        visible_trees[(r, 0)] = true;
+        visible_trees[(r, matrix.shape()[1] - 1)] = true;
     }
     for c in 0..matrix.shape()[1] {
-        visible_trees.insert((0, c));
-        visible_trees.insert((matrix.shape()[0] - 1, c));
+        visible_trees[(0, c)] = true;
+        visible_trees[(matrix.shape()[0] - 1, c)] = true;
     }
 
     // LEFT
     let mut max_trees = matrix.slice(s![.., 0]).to_owned();
     for c in 0..matrix.shape()[1] {
         for r in 0..matrix.shape()[0] {
-            if (!visible_trees.contains(&(r, c))) & (matrix[(r, c)] > max_trees[r]) {
-                visible_trees.insert((r, c));
+            if !visible_trees[(r, c)] & (matrix[(r, c)] > max_trees[r]) {
+                visible_trees[(r, c)] = true;
             }
             if max_trees[r] < matrix[(r, c)] {
                 max_trees[r] = matrix[(r, c)];
@@ -73,8 +93,8 @@ fn find_visible_trees(matrix: ArrayView2<i32>) -> HashSet<(usize, usize)> {
     let mut max_trees = matrix.slice(s![.., -1]).to_owned();
     for c in (0..matrix.shape()[1]).rev() {
         for r in 0..matrix.shape()[0] {
-            if (!visible_trees.contains(&(r, c))) & (matrix[(r, c)] > max_trees[r]) {
-                visible_trees.insert((r, c));
+            if !visible_trees[(r, c)] & (matrix[(r, c)] > max_trees[r]) {
+                visible_trees[(r, c)] = true;
             }
             if max_trees[r] < matrix[(r, c)] {
                 max_trees[r] = matrix[(r, c)];
@@ -86,8 +106,8 @@ fn find_visible_trees(matrix: ArrayView2<i32>) -> HashSet<(usize, usize)> {
     let mut max_trees = matrix.slice(s![0, ..]).to_owned();
     for r in 0..matrix.shape()[0] {
         for c in 0..matrix.shape()[1] {
-            if (!visible_trees.contains(&(r, c))) & (matrix[(r, c)] > max_trees[c]) {
-                visible_trees.insert((r, c));
+            if !visible_trees[(r, c)] & (matrix[(r, c)] > max_trees[c]) {
+                visible_trees[(r, c)] = true;
             }
             if max_trees[c] < matrix[(r, c)] {
                 max_trees[c] = matrix[(r, c)];
@@ -99,8 +119,8 @@ fn find_visible_trees(matrix: ArrayView2<i32>) -> HashSet<(usize, usize)> {
     let mut max_trees = matrix.slice(s![-1, ..]).to_owned();
     for r in (0..matrix.shape()[0]).rev() {
         for c in 0..matrix.shape()[1] {
-            if (!visible_trees.contains(&(r, c))) & (matrix[(r, c)] > max_trees[c]) {
-                visible_trees.insert((r, c));
+            if !visible_trees[(r, c)] & (matrix[(r, c)] > max_trees[c]) {
+                visible_trees[(r, c)] = true;
             }
             if max_trees[c] < matrix[(r, c)] {
                 max_trees[c] = matrix[(r, c)];
@@ -111,14 +131,63 @@ fn find_visible_trees(matrix: ArrayView2<i32>) -> HashSet<(usize, usize)> {
     visible_trees
 }
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
+fn find_visible_trees(matrix: ArrayView2<i32>) -> HashSet<(usize, usize)> {
+    visibility_grid(matrix)
+        .indexed_iter()
+        .filter(|(_, visible)| **visible)
+        .map(|(position, _)| position)
+        .collect()
+}
+
+/// returns the trees visible from the four cardinal directions
+/// plus the ones that are the tallest along any of the four diagonals
+pub fn find_visible_trees_diagonal(matrix: ArrayView2<i32>) -> HashSet<(usize, usize)> {
+    let mut visible_trees = find_visible_trees(matrix);
+    let (n_rows, n_cols) = (matrix.shape()[0] as i32, matrix.shape()[1] as i32);
+
+    for r in 0..n_rows {
+        for c in 0..n_cols {
+            if visible_trees.contains(&(r as usize, c as usize)) {
+                continue;
+            }
+            let height = matrix[(r as usize, c as usize)];
+            for (r_step, c_step) in [(-1, -1), (-1, 1), (1, -1), (1, 1)] {
+                let (mut dr, mut dc) = (r + r_step, c + c_step);
+                let mut visible = true;
+                while (dr >= 0) & (dr < n_rows) & (dc >= 0) & (dc < n_cols) {
+                    if matrix[(dr as usize, dc as usize)] >= height {
+                        visible = false;
+                        break;
+                    }
+                    dr += r_step;
+                    dc += c_step;
+                }
+                if visible {
+                    visible_trees.insert((r as usize, c as usize));
+                    break;
+                }
+            }
+        }
+    }
+
+    visible_trees
+}
+
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
     let matrix = parse_input(puzzle_input);
     let visible_trees = find_visible_trees(matrix.view());
 
-    Ok(visible_trees.len().to_string())
+    Ok(Answer::from(visible_trees.len()))
+}
+
+pub fn solve_pt1_diagonal(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let matrix = parse_input(puzzle_input);
+    let visible_trees = find_visible_trees_diagonal(matrix.view());
+
+    Ok(Answer::from(visible_trees.len()))
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
     let matrix = parse_input(puzzle_input);
     let visible_trees = find_visible_trees(matrix.view());
 
@@ -169,14 +238,21 @@ fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
         }
     }
 
-    Ok(highest_scene.to_string())
+    Ok(Answer::from(highest_scene))
 }
 
 #[cfg(test)]
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use ndarray::Array2;
+
+    use crate::Answer;
+
+    use super::{
+        find_visible_trees, parse_input, parse_input_multi, solve_pt1, solve_pt1_diagonal,
+        solve_pt2, visibility_grid,
+    };
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -185,7 +261,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!("21".to_string(), result);
+        assert_eq!(Answer::Int(21), result);
 
         Ok(())
     }
@@ -197,8 +273,70 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!("8".to_string(), result);
+        assert_eq!(Answer::Int(8), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_input_multi() -> Result<(), Box<dyn Error>> {
+        let matrix = parse_input_multi("10 10 10 10\n10 25 12 10\n10 11,99 10\n10 10 10 10\n")?;
+
+        assert_eq!(matrix.shape(), &[4, 4]);
+        assert_eq!(matrix[(1, 1)], 25);
+        assert_eq!(matrix[(2, 2)], 99);
+        assert_eq!(find_visible_trees(matrix.view()).len(), 16);
+
+        // blank lines are skipped
+        assert_eq!(parse_input_multi("\n1 2\n\n3 4\n")?.shape(), &[2, 2]);
+        assert!(parse_input_multi("").is_err());
+        assert!(parse_input_multi(" \n\n").is_err());
+        assert!(parse_input_multi(",\n,\n").is_err());
+
+        // ragged rows and invalid heights are errors naming the line
+        let error = parse_input_multi("1 2\n3 4 5\n").err().unwrap();
+        assert!(error.to_string().starts_with("line 2:"));
+        let error = parse_input_multi("1 2 3\n\n3 4\n").err().unwrap();
+        assert!(error.to_string().starts_with("line 3:"));
+        let error = parse_input_multi("1 2\n3 x\n").err().unwrap();
+        assert!(error.to_string().contains("'x'"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pt1_diagonal() -> Result<(), Box<dyn Error>> {
+        let puzzle_input = "19991\n91919\n99599\n91919\n19991\n".to_string();
+
+        assert_eq!(Answer::Int(16), solve_pt1(puzzle_input.clone())?);
+        assert_eq!(Answer::Int(17), solve_pt1_diagonal(puzzle_input)?);
 
         Ok(())
     }
+
+    #[test]
+    fn test_visibility_grid() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_08_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let matrix = parse_input(puzzle_input.clone());
+        let grid = visibility_grid(matrix.view());
+
+        assert_eq!(grid.shape(), matrix.shape());
+        assert_eq!(
+            Answer::from(grid.iter().filter(|visible| **visible).count()),
+            solve_pt1(puzzle_input)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_visibility_grid_empty() {
+        let matrix: Array2<i32> = Array2::zeros((0, 3));
+        assert_eq!(visibility_grid(matrix.view()).shape(), &[0, 3]);
+
+        let matrix: Array2<i32> = Array2::zeros((4, 0));
+        assert_eq!(visibility_grid(matrix.view()).shape(), &[4, 0]);
+    }
 }
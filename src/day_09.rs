@@ -1,36 +1,11 @@
-use std::{collections::HashSet, error::Error, fs::File, io::Read, str::FromStr, time::Instant};
+use std::{collections::HashSet, error::Error, str::FromStr};
 
-use log::info;
+use log::debug;
 use strum_macros::EnumString;
 
-use crate::ProblemPart;
-
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
-
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
-        }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
-}
+use crate::Answer;
+
+solve_day!(solve_pt1, solve_pt2);
 
 #[derive(Debug, EnumString)]
 enum Direction {
@@ -38,6 +13,10 @@ enum Direction {
     L,
     U,
     D,
+    UR,
+    UL,
+    DR,
+    DL,
 }
 
 struct Move {
@@ -80,12 +59,17 @@ impl Rope {
                     moved_tail.0 = moved_head.0 + 1;
                 }
 
-                let (start, end) = if prev_tail.0 > moved_tail.0 {
-                    (moved_tail.0, prev_tail.0)
+                // the positions are in travel order, from the previous tail to the moved one
+                tail_positions = if prev_tail.0 > moved_tail.0 {
+                    (moved_tail.0..=prev_tail.0)
+                        .rev()
+                        .map(|x| (x, prev_tail.1))
+                        .collect()
                 } else {
-                    (prev_tail.0, moved_tail.0)
+                    (prev_tail.0..=moved_tail.0)
+                        .map(|x| (x, prev_tail.1))
+                        .collect()
                 };
-                tail_positions = (start..=end).map(|x| (x, prev_tail.1)).collect();
             } else if moved_head.0 == prev_tail.0 {
                 if moved_head.1 > prev_tail.1 {
                     moved_tail.1 = moved_head.1 - 1;
@@ -93,12 +77,16 @@ impl Rope {
                     moved_tail.1 = moved_head.1 + 1;
                 }
 
-                let (start, end) = if prev_tail.1 > moved_tail.1 {
-                    (moved_tail.1, prev_tail.1)
+                tail_positions = if prev_tail.1 > moved_tail.1 {
+                    (moved_tail.1..=prev_tail.1)
+                        .rev()
+                        .map(|y| (prev_tail.0, y))
+                        .collect()
                 } else {
-                    (prev_tail.1, moved_tail.1)
+                    (prev_tail.1..=moved_tail.1)
+                        .map(|y| (prev_tail.0, y))
+                        .collect()
                 };
-                tail_positions = (start..=end).map(|y| (prev_tail.0, y)).collect();
             } else if (moved_head.0 > prev_tail.0) & (moved_head.1 > prev_tail.1) {
                 /* the head is bottom right of tail
                 . . T . .
@@ -108,9 +96,10 @@ impl Rope {
                 */
                 moved_tail = (moved_tail.0 + 1, moved_tail.1 + 1);
                 tail_positions.push(moved_tail);
-                let (next_moved_tail, next_tail_positions) = Rope::align(moved_head, moved_tail);
+                let (next_moved_tail, mut next_tail_positions) =
+                    Rope::align(moved_head, moved_tail);
                 moved_tail = next_moved_tail;
-                tail_positions.append(&mut next_tail_positions.clone());
+                tail_positions.append(&mut next_tail_positions);
             } else if (moved_head.0 < prev_tail.0) & (moved_head.1 < prev_tail.1) {
                 /* the head is upper left of tail
                 . . H . .
@@ -120,9 +109,10 @@ impl Rope {
                 */
                 moved_tail = (moved_tail.0 - 1, moved_tail.1 - 1);
                 tail_positions.push(moved_tail);
-                let (next_moved_tail, next_tail_positions) = Rope::align(moved_head, moved_tail);
+                let (next_moved_tail, mut next_tail_positions) =
+                    Rope::align(moved_head, moved_tail);
                 moved_tail = next_moved_tail;
-                tail_positions.append(&mut next_tail_positions.clone());
+                tail_positions.append(&mut next_tail_positions);
             } else if (moved_head.0 > prev_tail.0) & (moved_head.1 < prev_tail.1) {
                 /* the head is bottom left of tail
                 . . T . .
@@ -132,9 +122,10 @@ impl Rope {
                 */
                 moved_tail = (moved_tail.0 + 1, moved_tail.1 - 1);
                 tail_positions.push(moved_tail);
-                let (next_moved_tail, next_tail_positions) = Rope::align(moved_head, moved_tail);
+                let (next_moved_tail, mut next_tail_positions) =
+                    Rope::align(moved_head, moved_tail);
                 moved_tail = next_moved_tail;
-                tail_positions.append(&mut next_tail_positions.clone());
+                tail_positions.append(&mut next_tail_positions);
             } else {
                 /* the head is upper right of tail
                 . . H . .
@@ -144,9 +135,10 @@ impl Rope {
                 */
                 moved_tail = (moved_tail.0 - 1, moved_tail.1 + 1);
                 tail_positions.push(moved_tail);
-                let (next_moved_tail, next_tail_positions) = Rope::align(moved_head, moved_tail);
+                let (next_moved_tail, mut next_tail_positions) =
+                    Rope::align(moved_head, moved_tail);
                 moved_tail = next_moved_tail;
-                tail_positions.append(&mut next_tail_positions.clone());
+                tail_positions.append(&mut next_tail_positions);
             }
         }
         (moved_tail, tail_positions)
@@ -163,6 +155,10 @@ impl Rope {
             Direction::L => (0, -move_to_apply.steps),
             Direction::R => (0, move_to_apply.steps),
             Direction::D => (move_to_apply.steps, 0),
+            Direction::UR => (-move_to_apply.steps, move_to_apply.steps),
+            Direction::UL => (-move_to_apply.steps, -move_to_apply.steps),
+            Direction::DR => (move_to_apply.steps, move_to_apply.steps),
+            Direction::DL => (move_to_apply.steps, -move_to_apply.steps),
         };
 
         let moved_head = (prev_head.0 + x_step, prev_head.1 + y_step);
@@ -178,12 +174,27 @@ impl Rope {
     }
 }
 
+/// the cells visited by a knot
+pub type Positions = HashSet<(i32, i32)>;
+
 struct LongRope {
     head: (i32, i32),
     tails: Vec<(i32, i32)>,
+    /// positions visited by each knot, the first one is the head
+    paths: Vec<Positions>,
 }
 
 impl LongRope {
+    /// creates a rope with the given number of knots, head included,
+    /// all starting from the origin
+    fn new(knots: usize) -> LongRope {
+        LongRope {
+            head: (0, 0),
+            tails: vec![(0, 0); knots.saturating_sub(1)],
+            paths: vec![HashSet::from([(0, 0)]); knots.max(1)],
+        }
+    }
+
     fn apply_move(&mut self, move_to_apply: &Move) -> HashSet<(i32, i32)> {
         let mut tail_positions: HashSet<(i32, i32)> = HashSet::new();
         for _ in 0..move_to_apply.steps {
@@ -195,29 +206,44 @@ impl LongRope {
                 Direction::L => (0, -1),
                 Direction::R => (0, 1),
                 Direction::D => (1, 0),
+                // diagonal moves change both axes
+                Direction::UR => (-1, 1),
+                Direction::UL => (-1, -1),
+                Direction::DR => (1, 1),
+                Direction::DL => (1, -1),
             };
 
             let moved_head = (prev_head.0 + x_step, prev_head.1 + y_step);
             self.head = moved_head;
+            self.paths[0].insert(moved_head);
 
             let mut new_tails = Vec::new();
-            let mut last_tail_positions = Vec::new();
-
-            let (new_tail, _) = Rope::align(moved_head, self.tails[0]);
-            new_tails.push(new_tail);
-            let mut prev_tail = new_tail;
-            for current_tail in self.tails.iter().skip(1) {
-                let (new_tail, new_tail_positions) = Rope::align(prev_tail, *current_tail);
+            // each knot follows every intermediate position of the previous one,
+            // otherwise a knot moving more than one cell would drag the next ones
+            // without registering the cells they pass through
+            let mut prev_positions = vec![moved_head];
+
+            for (i, current_tail) in self.tails.iter().enumerate() {
+                let mut new_tail = *current_tail;
+                let mut new_tail_positions = Vec::new();
+                for prev_position in prev_positions {
+                    let (aligned_tail, mut aligned_positions) =
+                        Rope::align(prev_position, new_tail);
+                    new_tail = aligned_tail;
+                    new_tail_positions.append(&mut aligned_positions);
+                }
+                self.paths[i + 1].extend(&new_tail_positions);
                 new_tails.push(new_tail);
-                prev_tail = new_tail;
-                last_tail_positions = new_tail_positions;
+                // if the knot did not move, the next one has to follow its current position
+                prev_positions = if new_tail_positions.is_empty() {
+                    vec![new_tail]
+                } else {
+                    new_tail_positions
+                };
             }
 
-            for tail_pos in last_tail_positions {
-                if !tail_positions.contains(&tail_pos) {
-                    tail_positions.insert(tail_pos);
-                }
-            }
+            // when the rope has only the head, it is the last knot
+            tail_positions.extend(prev_positions);
 
             self.tails = new_tails;
         }
@@ -225,43 +251,101 @@ impl LongRope {
     }
 }
 
-fn parse_input(puzzle_input: String) -> Vec<Move> {
+fn parse_input(puzzle_input: String) -> Result<Vec<Move>, Box<dyn Error>> {
     let mut moves = Vec::new();
     for line in puzzle_input.lines() {
-        moves.push(Move {
-            direction: Direction::from_str(line.split_whitespace().next().unwrap()).unwrap(),
-            steps: line
-                .split_whitespace()
-                .nth(1)
-                .unwrap()
-                .parse::<i32>()
-                .unwrap(),
-        });
+        let mut tokens = line.split_whitespace();
+        let direction_token = tokens
+            .next()
+            .ok_or(format!("Missing direction in line '{line}'"))?;
+        let direction = Direction::from_str(direction_token)
+            .map_err(|_| format!("Unknown direction '{direction_token}' in line '{line}'"))?;
+        let steps_token = tokens
+            .next()
+            .ok_or(format!("Missing steps in line '{line}'"))?;
+        let steps = steps_token
+            .parse::<i32>()
+            .map_err(|_| format!("Invalid steps '{steps_token}' in line '{line}'"))?;
+        moves.push(Move { direction, steps });
     }
-    moves
+    Ok(moves)
 }
 
-fn print_positions(tail_positions: &HashSet<(i32, i32)>) {
-    let min_x = tail_positions.iter().map(|x| x.0).min().unwrap();
-    let max_x = tail_positions.iter().map(|x| x.0).max().unwrap();
-    let min_y = tail_positions.iter().map(|x| x.1).min().unwrap();
-    let max_y = tail_positions.iter().map(|x| x.1).max().unwrap();
-    for i in min_x..=max_x {
-        for j in min_y..=max_y {
-            if (i == 0) & (j == 0) {
-                print!("s");
-            } else if tail_positions.contains(&(i, j)) {
-                print!("#");
-            } else {
-                print!(".");
-            }
-        }
-        println!()
+/// returns the minimum and the maximum coordinates of the positions,
+/// or None when there are no positions
+pub fn bounding_box(positions: &HashSet<(i32, i32)>) -> Option<((i32, i32), (i32, i32))> {
+    let min_x = positions.iter().map(|x| x.0).min()?;
+    let max_x = positions.iter().map(|x| x.0).max()?;
+    let min_y = positions.iter().map(|x| x.1).min()?;
+    let max_y = positions.iter().map(|x| x.1).max()?;
+    Some(((min_x, min_y), (max_x, max_y)))
+}
+
+/// renders the visited positions as an svg image with a square for each cell
+/// and a red circle on the starting position
+///
+/// moving up decreases the row, hence rows are mapped directly on the svg y axis
+/// that grows downward and the image keeps the same orientation of the moves
+pub fn to_svg(tail_positions: &HashSet<(i32, i32)>) -> String {
+    let cell_size = 10;
+    // the starting position is always drawn
+    let ((min_x, min_y), (max_x, max_y)) = bounding_box(tail_positions).unwrap_or(((0, 0), (0, 0)));
+    let (min_x, min_y) = (min_x.min(0), min_y.min(0));
+    let (max_x, max_y) = (max_x.max(0), max_y.max(0));
+    let width = (max_y - min_y + 1) * cell_size;
+    let height = (max_x - min_x + 1) * cell_size;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+    );
+    let mut sorted_positions: Vec<&(i32, i32)> = tail_positions.iter().collect();
+    sorted_positions.sort();
+    for (i, j) in sorted_positions {
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{cell_size}\" height=\"{cell_size}\" fill=\"black\"/>\n",
+            (j - min_y) * cell_size,
+            (i - min_x) * cell_size
+        ));
+    }
+    svg.push_str(&format!(
+        "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"red\"/>\n",
+        -min_y * cell_size + cell_size / 2,
+        -min_x * cell_size + cell_size / 2,
+        cell_size / 2
+    ));
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// simulates a rope with the given number of knots, head included,
+/// and returns the number of positions visited by the last knot
+pub fn solve_knots(puzzle_input: &str, knots: usize) -> Result<usize, Box<dyn Error>> {
+    let moves = parse_input(puzzle_input.to_string())?;
+    let mut tail_positions: HashSet<(i32, i32)> = HashSet::new();
+    tail_positions.insert((0, 0));
+    let mut rope = LongRope::new(knots);
+
+    for move_to_apply in moves {
+        let new_tail_positions = rope.apply_move(&move_to_apply);
+        tail_positions.extend(&new_tail_positions);
+    }
+    Ok(tail_positions.len())
+}
+
+/// simulates a rope with the given number of knots, head included,
+/// and returns the positions visited by each knot, starting from the head
+pub fn knot_paths(puzzle_input: &str, knots: usize) -> Result<Vec<Positions>, Box<dyn Error>> {
+    let moves = parse_input(puzzle_input.to_string())?;
+    let mut rope = LongRope::new(knots);
+
+    for move_to_apply in moves {
+        rope.apply_move(&move_to_apply);
     }
+    Ok(rope.paths)
 }
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let moves = parse_input(puzzle_input);
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let moves = parse_input(puzzle_input)?;
     let mut tail_positions: HashSet<(i32, i32)> = HashSet::new();
     tail_positions.insert((0, 0));
     let mut rope = Rope {
@@ -272,34 +356,37 @@ fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
         let new_tail_positions = rope.apply_move(&move_to_apply);
         tail_positions.extend(&new_tail_positions);
     }
-    println!("{:?}", tail_positions);
-    print_positions(&tail_positions);
-    Ok(tail_positions.len().to_string())
+    debug!("{:?}", tail_positions);
+    debug!("{}", to_svg(&tail_positions));
+    Ok(Answer::from(tail_positions.len()))
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let moves = parse_input(puzzle_input);
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let moves = parse_input(puzzle_input)?;
     let mut tail_positions: HashSet<(i32, i32)> = HashSet::new();
     tail_positions.insert((0, 0));
-    let mut rope = LongRope {
-        head: (0, 0),
-        tails: vec![(0, 0); 9],
-    };
+    let mut rope = LongRope::new(10);
 
     for move_to_apply in moves {
         let new_tail_positions = rope.apply_move(&move_to_apply);
         tail_positions.extend(&new_tail_positions);
     }
-    println!("{:?}", tail_positions);
-    print_positions(&tail_positions);
-    Ok(tail_positions.len().to_string())
+    debug!("{:?}", tail_positions);
+    debug!("{}", to_svg(&tail_positions));
+    Ok(Answer::from(tail_positions.len()))
 }
 
 #[cfg(test)]
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use crate::Answer;
+
+    use std::collections::HashSet;
+
+    use super::{
+        bounding_box, knot_paths, parse_input, solve_knots, solve_pt1, solve_pt2, to_svg, Rope,
+    };
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -308,7 +395,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!("13".to_string(), result);
+        assert_eq!(Answer::Int(13), result);
 
         Ok(())
     }
@@ -320,7 +407,177 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!("36".to_string(), result);
+        assert_eq!(Answer::Int(36), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_knots() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_09_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!(13, solve_knots(&puzzle_input, 2)?);
+        assert_eq!(3, solve_knots(&puzzle_input, 5)?);
+        assert_eq!(1, solve_knots(&puzzle_input, 10)?);
+
+        let mut file = File::open("inputs/day_09_example_2.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!(88, solve_knots(&puzzle_input, 2)?);
+        assert_eq!(64, solve_knots(&puzzle_input, 5)?);
+        assert_eq!(36, solve_knots(&puzzle_input, 10)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pt1_long_move() -> Result<(), Box<dyn Error>> {
+        let puzzle_input = "U 1\nR 50\nD 3\nL 50\n".to_string();
+        let result = solve_pt1(puzzle_input)?;
+
+        assert_eq!(Answer::Int(101), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_knot_paths() -> Result<(), Box<dyn Error>> {
+        // the head never crosses its own path so it visits one cell per step plus the origin
+        let paths = knot_paths("R 4\nU 4\nL 3\n", 3)?;
+
+        assert_eq!(3, paths.len());
+        assert_eq!(12, paths[0].len());
+
+        let mut file = File::open("inputs/day_09_example_2.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let paths = knot_paths(&puzzle_input, 10)?;
+
+        assert_eq!(10, paths.len());
+        assert_eq!(88, paths[1].len());
+        assert_eq!(36, paths[9].len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pt2_intermediate_positions() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_09_example_2.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!(36, solve_knots(&puzzle_input, 10)?);
+
+        let puzzle_input = "U 1\nR 50\nD 3\nL 50\n";
+
+        assert_eq!(92, solve_knots(puzzle_input, 5)?);
+        assert_eq!(77, solve_knots(puzzle_input, 10)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_svg() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_09_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let paths = knot_paths(&puzzle_input, 2)?;
+        let svg = to_svg(&paths[1]);
+
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(13, svg.matches("<rect").count());
+        assert_eq!(1, svg.matches("<circle").count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_input_errors() {
+        assert!(parse_input("X 3".to_string()).is_err());
+        assert!(parse_input("R x".to_string()).is_err());
+        assert!(parse_input("R".to_string()).is_err());
+        assert!(solve_pt1("R 4\nX 3\n".to_string()).is_err());
+        assert!(solve_knots("X 3", 2).is_err());
+        assert!(knot_paths("X 3", 2).is_err());
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let positions = HashSet::from([(0, 0), (-2, 3), (1, -1), (4, 2)]);
+        assert_eq!(Some(((-2, -1), (4, 3))), bounding_box(&positions));
+
+        assert_eq!(
+            Some(((5, 5), (5, 5))),
+            bounding_box(&HashSet::from([(5, 5)]))
+        );
+        assert_eq!(None, bounding_box(&HashSet::new()));
+    }
+
+    #[test]
+    fn test_diagonal_moves() -> Result<(), Box<dyn Error>> {
+        let puzzle_input = "UR 3\nDL 3\n";
+
+        // the tail follows the head along the diagonal and back
+        assert_eq!(3, solve_knots(puzzle_input, 2)?);
+        assert_eq!(Answer::Int(3), solve_pt1(puzzle_input.to_string())?);
+        let paths = knot_paths(puzzle_input, 2)?;
+        assert_eq!(HashSet::from([(0, 0), (-1, 1), (-2, 2)]), paths[1]);
+
+        // the head moves five cells away, too few to drag the last knot
+        assert_eq!(1, solve_knots("DR 5\n", 10)?);
+        assert_eq!(2, solve_knots("DR 10\n", 10)?);
+        let paths = knot_paths("UL 2\nDR 4\n", 3)?;
+        assert_eq!(
+            HashSet::from([(0, 0), (-1, -1), (-2, -2), (1, 1), (2, 2)]),
+            paths[0]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_align_travel_order() -> Result<(), Box<dyn Error>> {
+        // the positions go from the previous tail towards the head in every direction
+        assert_eq!(
+            ((0, 3), vec![(0, 0), (0, 1), (0, 2), (0, 3)]),
+            Rope::align((0, 4), (0, 0))
+        );
+        assert_eq!(
+            ((0, 1), vec![(0, 4), (0, 3), (0, 2), (0, 1)]),
+            Rope::align((0, 0), (0, 4))
+        );
+        assert_eq!(
+            ((-3, 0), vec![(0, 0), (-1, 0), (-2, 0), (-3, 0)]),
+            Rope::align((-4, 0), (0, 0))
+        );
+        // a diagonal step followed by a straight move up
+        assert_eq!(
+            ((-3, -1), vec![(-1, -1), (-1, -1), (-2, -1), (-3, -1)]),
+            Rope::align((-4, -1), (0, 0))
+        );
+
+        // a long rope moving left and then up left diagonally
+        let paths = knot_paths("L 4\nUL 3\n", 3)?;
+
+        assert_eq!(
+            HashSet::from([
+                (0, 0),
+                (0, -1),
+                (0, -2),
+                (0, -3),
+                (-1, -4),
+                (-2, -5),
+                (-3, -6)
+            ]),
+            paths[1]
+        );
+        assert_eq!(
+            HashSet::from([(0, 0), (0, -1), (0, -2), (-1, -3), (-2, -4), (-3, -5)]),
+            paths[2]
+        );
 
         Ok(())
     }
@@ -1,35 +1,44 @@
-use std::{collections::HashMap, error::Error, fs::File, io::Read, str::FromStr, time::Instant};
+use std::{error::Error, fs, io, str::FromStr, time::Instant};
 
 use log::info;
 use strum_macros::EnumString;
 
-use crate::ProblemPart;
+use crate::{utils::load_input, Answer, ProblemPart};
 
 pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
+    let puzzle_input = load_input(puzzle_input)?;
+    let result = solve_str(&puzzle_input, part)?;
+    info!("Problem solution is \n{}", result);
+    Ok(())
+}
+
+/// solves the part of the problem on the puzzle input given as a string
+pub fn solve_str(puzzle_input: &str, part: ProblemPart) -> Result<Answer, Box<dyn Error>> {
+    let puzzle_input = puzzle_input.to_string();
 
     let result = match part {
         ProblemPart::One => {
             info!("Start solving part 1");
             let start = Instant::now();
             let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
+            let duration = start.elapsed().as_millis();
+            info!("Solved part 1 in {duration} milli seconds.");
             result
         }
         ProblemPart::Two => {
             info!("Start solving part 2");
             let start = Instant::now();
             let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
+            let duration = start.elapsed().as_millis();
+            info!("Solved part 2 in {duration} milli seconds.");
             result
         }
+        ProblemPart::Both => Answer::Both(
+            Box::new(solve_str(&puzzle_input, ProblemPart::One)?),
+            Box::new(solve_str(&puzzle_input, ProblemPart::Two)?),
+        ),
     };
-    info!("Problem solution is \n{}", result);
-    Ok(())
+    Ok(result)
 }
 
 #[derive(EnumString)]
@@ -38,95 +47,56 @@ enum Instruction {
     Noop,
     #[strum(serialize = "addx")]
     Addx(i32),
+    #[strum(serialize = "mulx")]
+    Mulx(i32),
 }
 
 impl Instruction {
     fn cycles(&self) -> i32 {
         match self {
             Instruction::Addx(_) => 2,
+            Instruction::Mulx(_) => 3,
             Instruction::Noop => 1,
         }
     }
+
+    /// returns the state after the execution of the instruction
+    fn apply(&self, state: i32) -> i32 {
+        match self {
+            Instruction::Addx(value) => state + value,
+            Instruction::Mulx(value) => state * value,
+            Instruction::Noop => state,
+        }
+    }
 }
 
 struct Program {
     initial_state: i32,
     instructions: Vec<Instruction>,
-    /// maps the nth cycle to the program state
-    cycle_state_map: HashMap<i32, i32>,
 }
 
 impl Program {
     fn new(instructions: Vec<Instruction>) -> Program {
-        let initial_cycle = 1;
-        let initial_state = 1;
-
-        let cycle_state_map = instructions
-            .iter()
-            .scan((initial_cycle, initial_state), |acc, x| {
-                let cycle = acc.0 + x.cycles();
-                let state = acc.1 + {
-                    if let Instruction::Addx(value) = x {
-                        value
-                    } else {
-                        &0
-                    }
-                };
-                *acc = (cycle, state);
-                Some(*acc)
-            })
-            .collect();
-
         Program {
-            initial_state,
+            initial_state: 1,
             instructions,
-            cycle_state_map,
         }
     }
 
-    /// without executing the program returns that state the program has
-    /// at the given cycle
-    ///
-    /// None is returned if for that cycle the program terminated its execution
-    fn strength_at_nth_cycle(&self, cycle: i32) -> Option<i32> {
-        if cycle > self.program_len() {
-            None
-        } else {
-            // we find the index of the instruction under execution
-            if let Some(state) = self.cycle_state_map.get(&cycle) {
-                Some(*state * cycle)
-            } else {
-                self.cycle_state_map
-                    .get(&(cycle - 1))
-                    .map(|state| *state * cycle)
-                    .or(Some(self.initial_state))
-            }
-        }
-    }
-
-    /// without executing the program returns that state the program has
-    /// at the given cycle
-    ///
-    /// None is returned if for that cycle the program terminated its execution
-    fn state_at_nth_cycle(&self, cycle: i32) -> Option<i32> {
-        if cycle > self.program_len() {
-            None
-        } else {
-            // we find the index of the instruction under execution
-            if let Some(state) = self.cycle_state_map.get(&cycle) {
-                Some(*state)
-            } else {
-                self.cycle_state_map
-                    .get(&(cycle - 1))
-                    .copied()
-                    .or(Some(self.initial_state))
-            }
-        }
-    }
-
-    /// returns the length in cycles of the program
-    fn program_len(&self) -> i32 {
-        self.instructions.iter().map(|x| x.cycles()).sum()
+    /// executes the program step by step yielding the state
+    /// during each cycle, starting from the first one
+    fn into_states(self) -> impl Iterator<Item = i32> {
+        self.instructions
+            .into_iter()
+            .scan(self.initial_state, |state, instruction| {
+                let current_state = *state;
+                *state = instruction.apply(current_state);
+                Some(std::iter::repeat_n(
+                    current_state,
+                    instruction.cycles() as usize,
+                ))
+            })
+            .flatten()
     }
 }
 
@@ -135,7 +105,7 @@ fn parse_input(puzzle_input: String) -> Program {
     for line in puzzle_input.lines() {
         let instruction_name = line.split_whitespace().next().unwrap();
         let mut instruction = Instruction::from_str(instruction_name).unwrap();
-        if let Instruction::Addx(ref mut value) = instruction {
+        if let Instruction::Addx(ref mut value) | Instruction::Mulx(ref mut value) = instruction {
             *value = line
                 .split_whitespace()
                 .nth(1)
@@ -148,42 +118,130 @@ fn parse_input(puzzle_input: String) -> Program {
     Program::new(instructions)
 }
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let program = parse_input(puzzle_input);
-    let mut result = 0;
-    // per qualche motivo al ciclo 220 lo stato è 19 e non 18
-    let mut cycle = 20;
-    while program.program_len() >= cycle {
-        result += program.strength_at_nth_cycle(cycle).unwrap();
-        cycle += 40;
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    // the signal strength is sampled during the 20th cycle and then every 40 cycles
+    let result: i32 = states(&puzzle_input)
+        .zip(1..)
+        .filter(|(_, cycle)| cycle % 40 == 20)
+        .map(|(state, cycle)| state * cycle)
+        .sum();
+
+    Ok(Answer::from(result))
+}
+
+/// executes the program step by step yielding the state
+/// during each cycle, starting from the first one
+pub fn states(puzzle_input: &str) -> impl Iterator<Item = i32> {
+    parse_input(puzzle_input.to_string()).into_states()
+}
+
+/// returns the CRT screen with the given dimensions
+/// where a pixel is true if it is lit
+///
+/// the pixels drawn after the end of the program stay dark
+fn screen(puzzle_input: &str, width: i32, height: i32) -> Vec<Vec<bool>> {
+    let mut grid = vec![vec![false; width as usize]; height as usize];
+    for (i, sprite_mid_position) in (0..(width * height)).zip(states(puzzle_input)) {
+        let column = i % width;
+        grid[(i / width) as usize][column as usize] =
+            (sprite_mid_position - 1 <= column) & (column <= sprite_mid_position + 1);
     }
+    grid
+}
 
-    Ok(result.to_string())
+/// returns the 6x40 CRT screen where a pixel is true if it is lit
+pub fn render_grid(puzzle_input: &str) -> Vec<Vec<bool>> {
+    screen(puzzle_input, 40, 6)
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let program = parse_input(puzzle_input);
+/// draws the CRT screen with the given dimensions using '#' for
+/// lit pixels and '.' for dark ones
+pub fn render(puzzle_input: &str, width: i32, height: i32) -> String {
     let mut result = String::new();
-    // per qualche motivo al ciclo 220 lo stato è 19 e non 18
-    for i in 0..240 {
-        let sprite_mid_position = program.state_at_nth_cycle(i + 1).unwrap();
-        if (sprite_mid_position - 1 <= i % 40) & (i % 40 <= sprite_mid_position + 1) {
-            result.push('#');
-        } else {
-            result.push('.');
-        }
-        if ((i + 1) % 40 == 0) & (i + 1 > 0) {
-            result.push('\n');
+    for row in screen(puzzle_input, width, height) {
+        for pixel in row {
+            result.push(if pixel { '#' } else { '.' });
         }
+        result.push('\n');
     }
-    Ok(result)
+    result
+}
+
+/// writes the CRT screen as a plain PBM image, 1 for lit pixels and 0 for dark ones
+pub fn write_pbm(grid: &[Vec<bool>], path: &str) -> io::Result<()> {
+    let width = grid.first().map(|row| row.len()).unwrap_or(0);
+    let mut image = format!("P1\n{width} {}\n", grid.len());
+    for row in grid {
+        let pixels: Vec<&str> = row
+            .iter()
+            .map(|&pixel| if pixel { "1" } else { "0" })
+            .collect();
+        image.push_str(&pixels.join(" "));
+        image.push('\n');
+    }
+    fs::write(path, image)
+}
+
+/// 4x6 glyphs of the letters drawn by the CRT, each one is
+/// written row by row with '#' for lit pixels and '.' for dark ones
+const GLYPHS: [(char, &str); 17] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+/// decodes the letters drawn on the CRT screen
+///
+/// each letter is 4 pixels wide and it is followed by an empty column,
+/// glyphs that are not recognized are decoded as '?'
+pub fn ocr(grid: &[Vec<bool>]) -> String {
+    let width = grid.first().map(|row| row.len()).unwrap_or(0);
+    let mut result = String::new();
+    for start in (0..width).step_by(5) {
+        let glyph: String = grid
+            .iter()
+            .flat_map(|row| {
+                (start..start + 4).map(|c| match row.get(c) {
+                    Some(true) => '#',
+                    _ => '.',
+                })
+            })
+            .collect();
+        let letter = GLYPHS
+            .iter()
+            .find(|(_, pattern)| *pattern == glyph)
+            .map(|(letter, _)| *letter)
+            .unwrap_or('?');
+        result.push(letter);
+    }
+    result
+}
+
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    Ok(Answer::Text(render(&puzzle_input, 40, 6)))
 }
 
 #[cfg(test)]
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use crate::Answer;
+
+    use super::{ocr, render, render_grid, solve_pt1, solve_pt2, states, write_pbm};
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -192,7 +250,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!("13140".to_string(), result);
+        assert_eq!(Answer::Int(13140), result);
 
         Ok(())
     }
@@ -206,7 +264,152 @@ mod test {
         let right_result = String::from("##..##..##..##..##..##..##..##..##..##..\n###...###...###...###...###...###...###.\n####....####....####....####....####....\n#####.....#####.....#####.....#####.....\n######......######......######......####\n#######.......#######.......#######.....\n");
         println!("RESULT\n{result}");
         println!("\n\nRIGHT RESULT\n{right_result}");
-        assert_eq!(right_result, result);
+        assert_eq!(Answer::Text(right_result), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_grid() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_10_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let grid = render_grid(&puzzle_input);
+
+        assert_eq!(6, grid.len());
+        assert!(grid.iter().all(|row| row.len() == 40));
+        // the example lights groups of n pixels separated by n dark pixels in the nth row
+        for (r, row) in grid.iter().enumerate() {
+            for (c, pixel) in row.iter().enumerate() {
+                assert_eq!((c / (r + 2)) % 2 == 0, *pixel);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_small_screen() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_10_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let puzzle_input = puzzle_input
+            .lines()
+            .take(40)
+            .collect::<Vec<&str>>()
+            .join("\n");
+        let result = render(&puzzle_input, 20, 3);
+
+        assert_eq!(
+            "##..##..##..##..##..\n....................\n###...###...###...##\n".to_string(),
+            result
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_truncated_program() {
+        // the program lasts 3 cycles, the second row would be lit if it went on
+        let result = render("noop\nnoop\nnoop\n", 4, 2);
+
+        assert_eq!("###.\n....\n".to_string(), result);
+        assert_eq!("....\n".to_string(), render("", 4, 1));
+    }
+
+    #[test]
+    fn test_ocr() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_10.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!("EZFCHJAB".to_string(), ocr(&render_grid(&puzzle_input)));
+
+        let mut file = File::open("inputs/day_10_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!("????????".to_string(), ocr(&render_grid(&puzzle_input)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_state_at_sampled_cycles() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_10_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let yielded: Vec<i32> = states(&puzzle_input).collect();
+
+        for (cycle, state) in [
+            (20, 21),
+            (60, 19),
+            (100, 18),
+            (140, 21),
+            (180, 16),
+            (220, 18),
+        ] {
+            assert_eq!(Some(&state), yielded.get(cycle - 1));
+        }
+        assert_eq!(Some(&1), yielded.get(1));
+        assert_eq!(240, yielded.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_states() -> Result<(), Box<dyn Error>> {
+        let yielded: Vec<i32> = states("noop\naddx 3\naddx -5\nnoop\n").collect();
+
+        assert_eq!(vec![1, 1, 1, 4, 4, -1], yielded);
+
+        let mut file = File::open("inputs/day_10_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let strength: i32 = states(&puzzle_input)
+            .enumerate()
+            .filter(|(i, _)| (i + 1) % 40 == 20)
+            .map(|(i, state)| (i as i32 + 1) * state)
+            .sum();
+
+        assert_eq!(Answer::from(strength), solve_pt1(puzzle_input)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mulx() -> Result<(), Box<dyn Error>> {
+        let puzzle_input = "noop\naddx 3\nmulx 2\naddx -1\nnoop\n";
+        let yielded: Vec<i32> = states(puzzle_input).collect();
+
+        assert_eq!(vec![1, 1, 1, 4, 4, 4, 8, 8, 7], yielded);
+        // the sampled cycles are past the end of the program
+        assert_eq!(Answer::Int(0), solve_pt1(puzzle_input.to_string())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_pbm() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_10_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let grid = render_grid(&puzzle_input);
+
+        let path = std::env::temp_dir().join("aoc_2022_day_10.pbm");
+        let path = path.to_str().unwrap();
+        write_pbm(&grid, path)?;
+        let image = std::fs::read_to_string(path)?;
+        std::fs::remove_file(path)?;
+
+        let mut lines = image.lines();
+        assert_eq!(Some("P1"), lines.next());
+        assert_eq!(Some("40 6"), lines.next());
+        let rows: Vec<&str> = lines.collect();
+        assert_eq!(6, rows.len());
+        assert!(rows.iter().all(|row| row.split(' ').count() == 40));
+        // the first row starts with two lit pixels followed by two dark ones
+        assert!(rows[0].starts_with("1 1 0 0 1 1"));
 
         Ok(())
     }
@@ -1,61 +1,63 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{BTreeMap, VecDeque},
     error::Error,
-    fs::File,
-    io::Read,
-    time::Instant,
 };
 
-use log::info;
+use crate::Answer;
 
-use crate::ProblemPart;
+solve_day!(solve_pt1, solve_pt2);
 
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
+/// operand of a monkey operation
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Term {
+    Old,
+    Const(u128),
+}
 
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
+impl Term {
+    fn value(&self, old: u128) -> u128 {
+        match self {
+            Term::Old => old,
+            Term::Const(value) => *value,
         }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
+    }
+}
+
+/// operation that computes the new worry level from the old one
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Add(Term, Term),
+    Mul(Term, Term),
+}
+
+impl Op {
+    fn apply(&self, old: u128) -> u128 {
+        match self {
+            Op::Add(first, second) => first.value(old) + second.value(old),
+            Op::Mul(first, second) => first.value(old) * second.value(old),
         }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
+    }
 }
 
+#[derive(Clone)]
 struct Monkey {
     items: VecDeque<u128>,
-    operation: Box<dyn Fn(u128) -> u128>,
-    test: Box<dyn Fn(u128) -> bool>,
+    operation: Op,
     divisor: u128,
     true_branch_monkey: u128,
     false_branch_monkey: u128,
 }
 
 impl Monkey {
-    fn inspect_item(&mut self, no_divide: bool) -> (u128, u128) {
+    fn inspect_item(&mut self, relief_divisor: Option<u128>) -> (u128, u128) {
         let mut item = self.items.pop_front().unwrap();
-        item = (self.operation)(item);
+        item = self.operation.apply(item);
 
-        if !no_divide {
-            item = (item as f32 / 3.0).floor() as u128;
+        if let Some(divisor) = relief_divisor {
+            item /= divisor;
         }
 
-        if (self.test)(item) {
+        if item.is_multiple_of(self.divisor) {
             (self.true_branch_monkey, item)
         } else {
             (self.false_branch_monkey, item)
@@ -78,9 +80,9 @@ impl Monkey {
     }
 }
 
-fn parse_input(puzzle_input: String) -> HashMap<u128, Monkey> {
+fn parse_input(puzzle_input: String) -> BTreeMap<u128, Monkey> {
     // push items back
-    let mut monkeys = HashMap::new();
+    let mut monkeys = BTreeMap::new();
     for block in puzzle_input.split("\n\n") {
         let mut lines = block.lines();
         let monkey_id = lines
@@ -115,7 +117,7 @@ fn parse_input(puzzle_input: String) -> HashMap<u128, Monkey> {
                 .to_string(),
         );
 
-        let (test, divisor) = parse_test(
+        let divisor = parse_test(
             lines
                 .next()
                 .unwrap()
@@ -147,7 +149,6 @@ fn parse_input(puzzle_input: String) -> HashMap<u128, Monkey> {
             Monkey {
                 items,
                 operation,
-                test,
                 divisor,
                 true_branch_monkey,
                 false_branch_monkey,
@@ -157,99 +158,78 @@ fn parse_input(puzzle_input: String) -> HashMap<u128, Monkey> {
     monkeys
 }
 
-fn parse_operation(operation: String) -> Box<dyn Fn(u128) -> u128> {
-    let first_term = operation.split_whitespace().next().unwrap().parse::<u128>();
-    let second_term = operation
-        .split_ascii_whitespace()
-        .nth(2)
-        .unwrap()
-        .parse::<u128>();
+fn parse_term(term: &str) -> Term {
+    if term == "old" {
+        Term::Old
+    } else if let Ok(value) = term.parse::<u128>() {
+        Term::Const(value)
+    } else {
+        panic!("unknown operand {term}");
+    }
+}
+
+fn parse_operation(operation: String) -> Op {
+    let first_term = parse_term(operation.split_whitespace().next().unwrap());
+    let second_term = parse_term(operation.split_ascii_whitespace().nth(2).unwrap());
 
     if operation.contains('+') {
-        Box::new(move |old| {
-            let first_operand = first_term.clone().unwrap_or(old);
-            let second_operand = second_term.clone().unwrap_or(old);
-            first_operand + second_operand
-        })
+        Op::Add(first_term, second_term)
     } else if operation.contains('*') {
-        Box::new(move |old| {
-            let first_operand = first_term.clone().unwrap_or(old);
-            let second_operand = second_term.clone().unwrap_or(old);
-            first_operand * second_operand
-        })
+        Op::Mul(first_term, second_term)
     } else {
         panic!("unknown operator");
     }
 }
 
-fn parse_test(test: String) -> (Box<dyn Fn(u128) -> bool>, u128) {
+/// returns the divisor used by the monkey to choose where to throw the item
+fn parse_test(test: String) -> u128 {
     if test.contains("divisible by ") {
-        let num = test
-            .split("divisible by ")
+        test.split("divisible by ")
             .nth(1)
             .unwrap()
             .parse::<u128>()
-            .unwrap();
-        (Box::new(move |old| (old % num) == 0), num)
+            .unwrap()
     } else {
         panic!("unknown test");
     }
 }
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let mut monkeys = parse_input(puzzle_input);
-    let mut monkey_businesses: HashMap<u128, u128> = HashMap::new();
-
-    for _ in 0..20 {
-        for i in 0..monkeys.len() {
-            let current_monkey_id = i as u128;
-            while monkeys.get(&current_monkey_id).unwrap().has_items() {
-                *monkey_businesses.entry(current_monkey_id).or_insert(0) += 1;
-                let (destination_monkey, level) = monkeys
-                    .get_mut(&current_monkey_id)
-                    .unwrap()
-                    .inspect_item(false);
-                monkeys
-                    .get_mut(&destination_monkey)
-                    .unwrap()
-                    .add_item(level);
-            }
-        }
-    }
-
-    let mut monkey_businesses_vec = monkey_businesses.into_iter().collect::<Vec<(u128, u128)>>();
-    monkey_businesses_vec.sort_by(|a, b| a.1.cmp(&b.1));
-    Ok((monkey_businesses_vec.last().unwrap().1
-        * monkey_businesses_vec
-            .get(monkey_businesses_vec.len() - 2)
-            .unwrap()
-            .1)
-        .to_string())
-}
-
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let mut monkeys = parse_input(puzzle_input);
-    let mut monkey_businesses: HashMap<u128, u128> = HashMap::new();
+/// plays the given number of rounds and returns the number of inspected
+/// items of each monkey, ordered by monkey id
+///
+/// when the relief divisor is given the worry level is divided by it after
+/// each inspection, otherwise worry levels are kept bounded using the product
+/// of the monkeys divisors
+pub fn inspection_counts(
+    puzzle_input: &str,
+    rounds: usize,
+    relief_divisor: Option<u128>,
+) -> Vec<u128> {
+    let mut monkeys = parse_input(puzzle_input.to_string());
+    let mut monkey_businesses: BTreeMap<u128, u128> = BTreeMap::new();
 
     let divisors_prod = monkeys
         .values()
         .map(|x| x.divisor)
         .reduce(|acc, x| acc * x)
         .unwrap();
+    // monkeys are processed in id order even if ids are not contiguous
+    let monkey_ids: Vec<u128> = monkeys.keys().copied().collect();
 
-    for _ in 0..10000 {
-        for i in 0..monkeys.len() {
-            let current_monkey_id = i as u128;
+    for _ in 0..rounds {
+        for &current_monkey_id in monkey_ids.iter() {
             while monkeys.get(&current_monkey_id).unwrap().has_items() {
-                monkeys
-                    .get_mut(&current_monkey_id)
-                    .unwrap()
-                    .normalize_worry_levels(divisors_prod);
+                if relief_divisor.is_none() {
+                    monkeys
+                        .get_mut(&current_monkey_id)
+                        .unwrap()
+                        .normalize_worry_levels(divisors_prod);
+                }
                 *monkey_businesses.entry(current_monkey_id).or_insert(0) += 1;
                 let (destination_monkey, level) = monkeys
                     .get_mut(&current_monkey_id)
                     .unwrap()
-                    .inspect_item(true);
+                    .inspect_item(relief_divisor);
                 monkeys
                     .get_mut(&destination_monkey)
                     .unwrap()
@@ -258,21 +238,40 @@ fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
         }
     }
 
-    let mut monkey_businesses_vec = monkey_businesses.into_iter().collect::<Vec<(u128, u128)>>();
-    monkey_businesses_vec.sort_by(|a, b| a.1.cmp(&b.1));
-    Ok((monkey_businesses_vec.last().unwrap().1
-        * monkey_businesses_vec
-            .get(monkey_businesses_vec.len() - 2)
-            .unwrap()
-            .1)
-        .to_string())
+    monkey_ids
+        .iter()
+        .map(|id| *monkey_businesses.get(id).unwrap_or(&0))
+        .collect()
+}
+
+/// plays the given number of rounds and returns the monkey business,
+/// i.e., the product of the two highest inspection counts
+pub fn run(puzzle_input: &str, rounds: usize, relief_divisor: Option<u128>) -> u128 {
+    let mut counts = inspection_counts(puzzle_input, rounds, relief_divisor);
+    counts.sort();
+    counts.iter().rev().take(2).product()
+}
+
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let monkey_business = run(&puzzle_input, 20, Some(3));
+    Ok(Answer::Int(i128::try_from(monkey_business)?))
+}
+
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let monkey_business = run(&puzzle_input, 10000, None);
+    Ok(Answer::Int(i128::try_from(monkey_business)?))
 }
 
 #[cfg(test)]
 mod test {
-    use std::{error::Error, fs::File, io::Read};
+    use std::{collections::VecDeque, error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use crate::Answer;
+
+    use super::{
+        inspection_counts, parse_input, parse_operation, run, solve_pt1, solve_pt2, Monkey, Op,
+        Term,
+    };
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -281,7 +280,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!("10605".to_string(), result);
+        assert_eq!(Answer::Int(10605), result);
 
         Ok(())
     }
@@ -293,8 +292,116 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!("2713310158".to_string(), result);
+        assert_eq!(Answer::Int(2713310158), result);
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_operation() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_11_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let monkeys = parse_input(puzzle_input.clone());
+
+        // the example operations written as closures, as they used to be represented
+        let closures: Vec<Box<dyn Fn(u128) -> u128>> = vec![
+            Box::new(|old| old * 19),
+            Box::new(|old| old + 6),
+            Box::new(|old| old * old),
+            Box::new(|old| old + 3),
+        ];
+        for (i, closure) in closures.iter().enumerate() {
+            let operation = monkeys.get(&(i as u128)).unwrap().operation;
+            for old in [0, 1, 7, 79, 1_000_000] {
+                assert_eq!(closure(old), operation.apply(old));
+            }
+        }
+
+        assert_eq!(
+            Op::Mul(Term::Old, Term::Old),
+            parse_operation("old * old".to_string())
+        );
+        assert_eq!(Answer::Int(10605), solve_pt1(puzzle_input)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_11_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!(20, run(&puzzle_input, 1, Some(3)));
+        assert_eq!(10605, run(&puzzle_input, 20, Some(3)));
+        assert_eq!(24, run(&puzzle_input, 1, None));
+        assert_eq!(10197, run(&puzzle_input, 20, None));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspection_counts() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_11_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!(
+            vec![101, 95, 7, 105],
+            inspection_counts(&puzzle_input, 20, Some(3))
+        );
+        assert_eq!(
+            vec![99, 97, 8, 103],
+            inspection_counts(&puzzle_input, 20, None)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_relief_integer_division() {
+        // 100000001 is not representable as f32 and dividing it as a float gives 33333334
+        let mut monkey = Monkey {
+            items: VecDeque::from([100_000_001]),
+            operation: Op::Add(Term::Old, Term::Const(0)),
+            divisor: 33_333_333,
+            true_branch_monkey: 1,
+            false_branch_monkey: 2,
+        };
+
+        assert_eq!((1, 33_333_333), monkey.inspect_item(Some(3)));
+    }
+
+    #[test]
+    fn test_non_contiguous_ids() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_11_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let puzzle_input = puzzle_input
+            .replace("Monkey 3:", "Monkey 5:")
+            .replace("monkey 3", "monkey 5");
+
+        assert_eq!(
+            vec![101, 95, 7, 105],
+            inspection_counts(&puzzle_input, 20, Some(3))
+        );
+        assert_eq!(Answer::Int(10605), solve_pt1(puzzle_input)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_operation_terms() {
+        assert_eq!(
+            Op::Mul(Term::Old, Term::Old),
+            parse_operation("old * old".to_string())
+        );
+        assert_eq!(
+            Op::Mul(Term::Const(5), Term::Old),
+            parse_operation("5 * old".to_string())
+        );
+        assert_eq!(25, parse_operation("old * old".to_string()).apply(5));
+        assert_eq!(35, parse_operation("5 * old".to_string()).apply(7));
+    }
 }
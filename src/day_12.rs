@@ -1,43 +1,14 @@
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
     error::Error,
-    f32::INFINITY,
-    fs::File,
-    io::Read,
-    time::Instant,
 };
 
-use log::info;
 use ndarray::{Array2, ArrayView2};
 
-use crate::ProblemPart;
-
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
-
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
-        }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
-}
+use crate::Answer;
+
+solve_day!(solve_pt1, solve_pt2);
 
 fn parse_input(puzzle_input: String) -> (Array2<i32>, (usize, usize), (usize, usize)) {
     let lines = puzzle_input.lines().collect::<Vec<&str>>();
@@ -65,28 +36,60 @@ fn parse_input(puzzle_input: String) -> (Array2<i32>, (usize, usize), (usize, us
     (heightmap, start, end)
 }
 
-fn find_neighbors(node: &(usize, usize), heightmap: ArrayView2<i32>) -> Vec<(usize, usize)> {
-    // look at neighbors and keep nodes with difference of value at most 1
+const CARDINAL_OFFSETS: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const DIAGONAL_OFFSETS: [(isize, isize); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+/// returns the neighbors that can be reached from the node, i.e., the ones that are
+/// at most one higher than it, moving up, down, left, right and, if enabled, diagonally
+fn find_neighbors(
+    node: &(usize, usize),
+    heightmap: ArrayView2<i32>,
+    diagonal: bool,
+) -> Vec<(usize, usize)> {
+    let offsets = if diagonal {
+        [CARDINAL_OFFSETS, DIAGONAL_OFFSETS].concat()
+    } else {
+        CARDINAL_OFFSETS.to_vec()
+    };
+    let (rows, columns) = heightmap.dim();
+
+    offsets
+        .into_iter()
+        .filter_map(|(dr, dc)| {
+            let r = node.0.checked_add_signed(dr).filter(|&r| r < rows)?;
+            let c = node.1.checked_add_signed(dc).filter(|&c| c < columns)?;
+            Some((r, c))
+        })
+        .filter(|&neighbor| heightmap[*node] + 1 >= heightmap[neighbor])
+        .collect()
+}
+
+fn find_reverse_neighbors(
+    node: &(usize, usize),
+    heightmap: ArrayView2<i32>,
+) -> Vec<(usize, usize)> {
+    // look at neighbors from which the node can be reached, i.e.,
+    // the ones that are at most one lower than it
     let mut neighbors = Vec::new();
 
     // up
-    if node.0 >= 1 && heightmap[*node] + 1 >= heightmap[(node.0 - 1, node.1)] {
+    if node.0 >= 1 && heightmap[(node.0 - 1, node.1)] + 1 >= heightmap[*node] {
         neighbors.push((node.0 - 1, node.1));
     }
 
     // down
-    if node.0 < heightmap.shape()[0] - 1 && heightmap[*node] + 1 >= heightmap[(node.0 + 1, node.1)]
+    if node.0 < heightmap.shape()[0] - 1 && heightmap[(node.0 + 1, node.1)] + 1 >= heightmap[*node]
     {
         neighbors.push((node.0 + 1, node.1));
     }
 
     // left
-    if node.1 >= 1 && heightmap[*node] + 1 >= heightmap[(node.0, node.1 - 1)] {
+    if node.1 >= 1 && heightmap[(node.0, node.1 - 1)] + 1 >= heightmap[*node] {
         neighbors.push((node.0, node.1 - 1));
     }
 
     // right
-    if node.1 < heightmap.shape()[1] - 1 && heightmap[*node] + 1 >= heightmap[(node.0, node.1 + 1)]
+    if node.1 < heightmap.shape()[1] - 1 && heightmap[(node.0, node.1 + 1)] + 1 >= heightmap[*node]
     {
         neighbors.push((node.0, node.1 + 1));
     }
@@ -94,102 +97,184 @@ fn find_neighbors(node: &(usize, usize), heightmap: ArrayView2<i32>) -> Vec<(usi
     neighbors
 }
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let (heightmap, start, end) = parse_input(puzzle_input);
-    let mut unvisited_set: VecDeque<(usize, usize)> = VecDeque::new();
+/// maps each reached node to the node from which it has been reached
+type Parents = HashMap<(usize, usize), (usize, usize)>;
+
+/// breadth first search from start that stops as soon as the end is reached,
+/// when there is no end every reachable node is visited
+///
+/// returns the distance from start of each reached node, since all the steps
+/// have the same length the first time a node is reached is through a shortest path.
+/// Nodes that are not reached are not in the map and have distance u32::MAX.
+///
+/// It also returns the parent of each reached node, i.e., the node from
+/// which it has been reached
+fn search(
+    heightmap: ArrayView2<i32>,
+    start: (usize, usize),
+    end: Option<(usize, usize)>,
+    diagonal: bool,
+) -> (HashMap<(usize, usize), u32>, Parents) {
+    let mut frontier: VecDeque<(usize, usize)> = VecDeque::new();
     let mut visited_set: HashSet<(usize, usize)> = HashSet::new();
-    let mut tentative_distance: HashMap<(usize, usize), f32> = HashMap::new();
-    let mut current_node: (usize, usize) = start;
-    tentative_distance.insert(current_node, 0.0);
-    unvisited_set.push_back(start);
-    let mut destination_node_marked = false;
-
-    while !unvisited_set.is_empty() & !destination_node_marked {
-        current_node = unvisited_set.pop_front().unwrap();
-        for neighbor_node in find_neighbors(&current_node, heightmap.view()) {
-            // neighbor distance is always 1 because only one step of one is allowed
-            let neighbor_distance = 1.0;
-            let distance = neighbor_distance + tentative_distance.get(&current_node).unwrap();
-            let current_neighbor_distance =
-                tentative_distance.entry(neighbor_node).or_insert(INFINITY);
-            if *current_neighbor_distance > distance {
-                *current_neighbor_distance = distance;
+    let mut distances: HashMap<(usize, usize), u32> = HashMap::new();
+    let mut parents: Parents = HashMap::new();
+    distances.insert(start, 0);
+    visited_set.insert(start);
+    frontier.push_back(start);
+
+    while let Some(current_node) = frontier.pop_front() {
+        if Some(current_node) == end {
+            break;
+        }
+        let distance = distances[&current_node] + 1;
+        for neighbor_node in find_neighbors(&current_node, heightmap, diagonal) {
+            if visited_set.insert(neighbor_node) {
+                distances.insert(neighbor_node, distance);
+                parents.insert(neighbor_node, current_node);
+                frontier.push_back(neighbor_node);
             }
+        }
+    }
+    (distances, parents)
+}
 
-            if !visited_set.contains(&neighbor_node) & !unvisited_set.contains(&neighbor_node) {
-                unvisited_set.push_back(neighbor_node);
-            }
+/// returns the cells of the shortest path from start to end, both included,
+/// or None if the end cannot be reached
+pub fn shortest_path(puzzle_input: &str) -> Option<Vec<(usize, usize)>> {
+    let (heightmap, start, end) = parse_input(puzzle_input.to_string());
+    let (distances, parents) = search(heightmap.view(), start, Some(end), false);
+
+    if !distances.contains_key(&end) {
+        return None;
+    }
+    let mut path = vec![end];
+    let mut current_node = end;
+    while let Some(parent) = parents.get(&current_node) {
+        path.push(*parent);
+        current_node = *parent;
+    }
+    path.reverse();
+    Some(path)
+}
 
-            if end == neighbor_node {
-                destination_node_marked = true;
+type Node = (usize, usize);
+
+/// returns the length of the shortest path from start to end found with A*,
+/// or None if the end cannot be reached
+///
+/// the Manhattan distance to the end never overestimates the remaining steps,
+/// since each step moves by one cell, hence the first time the end is popped
+/// its distance is the shortest one
+pub fn shortest_astar(puzzle_input: &str) -> Option<u32> {
+    let (heightmap, start, end) = parse_input(puzzle_input.to_string());
+    let heuristic = |node: (usize, usize)| (node.0.abs_diff(end.0) + node.1.abs_diff(end.1)) as u32;
+
+    // nodes are popped by lowest estimated total distance, then by distance from start
+    let mut open_set: BinaryHeap<Reverse<(u32, u32, Node)>> = BinaryHeap::new();
+    let mut distances: HashMap<(usize, usize), u32> = HashMap::new();
+    distances.insert(start, 0);
+    open_set.push(Reverse((heuristic(start), 0, start)));
+
+    while let Some(Reverse((_, distance, current_node))) = open_set.pop() {
+        if current_node == end {
+            return Some(distance);
+        }
+        if distance > distances[&current_node] {
+            // the node has already been reached through a shorter path
+            continue;
+        }
+        for neighbor_node in find_neighbors(&current_node, heightmap.view(), false) {
+            let neighbor_distance = distance + 1;
+            if distances
+                .get(&neighbor_node)
+                .is_none_or(|&d| neighbor_distance < d)
+            {
+                distances.insert(neighbor_node, neighbor_distance);
+                open_set.push(Reverse((
+                    neighbor_distance + heuristic(neighbor_node),
+                    neighbor_distance,
+                    neighbor_node,
+                )));
             }
         }
-        visited_set.insert(current_node);
     }
+    None
+}
 
-    Ok(tentative_distance.get(&end).unwrap().to_string())
+/// returns the shortest distance from start of each cell, -1 if it cannot be reached
+pub fn distance_grid(puzzle_input: &str) -> Array2<i32> {
+    let (heightmap, start, _) = parse_input(puzzle_input.to_string());
+    let (distances, _) = search(heightmap.view(), start, None, false);
+
+    let mut grid: Array2<i32> = Array2::from_elem(heightmap.dim(), -1);
+    for (node, distance) in distances {
+        grid[node] = distance as i32;
+    }
+    grid
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
     let (heightmap, start, end) = parse_input(puzzle_input);
+    let (distances, _) = search(heightmap.view(), start, Some(end), false);
 
-    let mut candiates_starts: Vec<(usize, usize)> = vec![start];
-    for r in 0..heightmap.shape()[0] {
-        for c in 0..heightmap.shape()[1] {
-            if heightmap[(r, c)] == heightmap[start] {
-                candiates_starts.push((r, c));
-            }
-        }
-    }
+    let distance = distances.get(&end).ok_or("no path from start to end")?;
 
-    let mut minimum_distance = INFINITY;
-
-    for start in candiates_starts {
-        info!("Processing {:?}", start);
-
-        let mut unvisited_set: VecDeque<(usize, usize)> = VecDeque::new();
-        let mut visited_set: HashSet<(usize, usize)> = HashSet::new();
-        let mut tentative_distance: HashMap<(usize, usize), f32> = HashMap::new();
-        let mut current_node: (usize, usize) = start;
-        tentative_distance.insert(current_node, 0.0);
-        unvisited_set.push_back(start);
-        let mut destination_node_marked = false;
-
-        while !unvisited_set.is_empty() & !destination_node_marked {
-            current_node = unvisited_set.pop_front().unwrap();
-            for neighbor_node in find_neighbors(&current_node, heightmap.view()) {
-                // neighbor distance is always 1 because only one step of one is allowed
-                let neighbor_distance = 1.0;
-                let distance = neighbor_distance + tentative_distance.get(&current_node).unwrap();
-                let current_neighbor_distance =
-                    tentative_distance.entry(neighbor_node).or_insert(INFINITY);
-                if *current_neighbor_distance > distance {
-                    *current_neighbor_distance = distance;
-                }
+    Ok(Answer::from(*distance))
+}
 
-                if !visited_set.contains(&neighbor_node) & !unvisited_set.contains(&neighbor_node) {
-                    unvisited_set.push_back(neighbor_node);
-                }
+/// returns the length of the shortest path from any of the lowest cells to the end
+///
+/// a single breadth first search is done backward from the end until the first
+/// lowest cell is reached, None is returned if none of them can reach the end
+pub fn shortest_from_any_low(puzzle_input: &str) -> Option<u32> {
+    let (heightmap, start, end) = parse_input(puzzle_input.to_string());
+    let lowest_height = heightmap[start];
 
-                if end == neighbor_node {
-                    destination_node_marked = true;
-                }
-            }
-            visited_set.insert(current_node);
-        }
+    let mut frontier: VecDeque<((usize, usize), u32)> = VecDeque::new();
+    let mut visited_set: HashSet<(usize, usize)> = HashSet::new();
+    visited_set.insert(end);
+    frontier.push_back((end, 0));
 
-        if *tentative_distance.get(&end).unwrap_or(&INFINITY) < minimum_distance {
-            minimum_distance = *tentative_distance.get(&end).unwrap();
+    while let Some((current_node, distance)) = frontier.pop_front() {
+        if heightmap[current_node] == lowest_height {
+            return Some(distance);
+        }
+        for neighbor_node in find_reverse_neighbors(&current_node, heightmap.view()) {
+            if visited_set.insert(neighbor_node) {
+                frontier.push_back((neighbor_node, distance + 1));
+            }
         }
     }
-    Ok(minimum_distance.to_string())
+    None
+}
+
+/// like part one but it is also possible to move diagonally,
+/// u32::MAX is returned if the end cannot be reached
+pub fn solve_pt1_diagonal(puzzle_input: &str) -> u32 {
+    let (heightmap, start, end) = parse_input(puzzle_input.to_string());
+    let (distances, _) = search(heightmap.view(), start, Some(end), true);
+    distances.get(&end).copied().unwrap_or(u32::MAX)
+}
+
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let distance =
+        shortest_from_any_low(&puzzle_input).ok_or("no lowest cell can reach the end")?;
+    Ok(Answer::from(distance))
 }
 
 #[cfg(test)]
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use std::time::Instant;
+
+    use crate::Answer;
+
+    use super::{
+        distance_grid, parse_input, search, shortest_astar, shortest_from_any_low, shortest_path,
+        solve_pt1, solve_pt1_diagonal, solve_pt2,
+    };
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -198,7 +283,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!("31".to_string(), result);
+        assert_eq!(Answer::Int(31), result);
 
         Ok(())
     }
@@ -210,7 +295,154 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!("29".to_string(), result);
+        assert_eq!(Answer::Int(29), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pt1_actual() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_12.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let start = Instant::now();
+        let result = solve_pt1(puzzle_input)?;
+
+        assert_eq!(Answer::Int(350), result);
+        assert!(start.elapsed().as_secs() < 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pt2_actual() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_12.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let start = Instant::now();
+        let result = shortest_from_any_low(&puzzle_input);
+
+        assert_eq!(Some(349), result);
+        assert!(start.elapsed().as_secs() < 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_integer_distances() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_12_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let (heightmap, start, end) = parse_input(puzzle_input.clone());
+        let (distances, _) = search(heightmap.view(), start, Some(end), false);
+
+        assert_eq!(Some(&0), distances.get(&start));
+        assert_eq!(Some(&31), distances.get(&end));
+        assert_eq!(Some(29), shortest_from_any_low(&puzzle_input));
+        assert_eq!(Answer::Int(31), solve_pt1(puzzle_input.clone())?);
+        assert_eq!(Answer::Int(29), solve_pt2(puzzle_input)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_path() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_12_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let (_, start, end) = parse_input(puzzle_input.clone());
+        let path = shortest_path(&puzzle_input).unwrap();
+
+        assert_eq!(32, path.len());
+        assert_eq!(Some(&start), path.first());
+        assert_eq!(Some(&end), path.last());
+        for step in path.windows(2) {
+            assert_eq!(
+                1,
+                step[0].0.abs_diff(step[1].0) + step[0].1.abs_diff(step[1].1)
+            );
+        }
+
+        assert_eq!(None, shortest_path("SbE\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pt1_unreachable_end() {
+        let result = solve_pt1("Sbcd\nabcz\nzzzE\n".to_string());
+
+        assert!(result.is_err());
+        assert_eq!(
+            "no path from start to end".to_string(),
+            result.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_pt2_unreachable_end() {
+        let puzzle_input = "Sbcd\nabcz\nzzzE\n";
+
+        assert_eq!(None, shortest_from_any_low(puzzle_input));
+        let result = solve_pt2(puzzle_input.to_string());
+        assert_eq!(
+            "no lowest cell can reach the end".to_string(),
+            result.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_shortest_astar() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_12_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!(Some(31), shortest_astar(&puzzle_input));
+        assert_eq!(Answer::Int(31), solve_pt1(puzzle_input)?);
+        assert_eq!(None, shortest_astar("Sbcd\nabcz\nzzzE\n"));
+
+        let mut file = File::open("inputs/day_12.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!(Some(350), shortest_astar(&puzzle_input));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_distance_grid() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_12_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let (_, start, end) = parse_input(puzzle_input.clone());
+        let grid = distance_grid(&puzzle_input);
+
+        assert_eq!((5, 8), grid.dim());
+        assert_eq!(0, grid[start]);
+        assert_eq!(31, grid[end]);
+        // every cell of the example can be reached
+        assert!(grid.iter().all(|&distance| distance >= 0));
+
+        let grid = distance_grid("Sbcd\nabcz\nzzzE\n");
+        assert_eq!(-1, grid[(2, 3)]);
+        assert_eq!(3, grid[(0, 3)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pt1_diagonal() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_12_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!(27, solve_pt1_diagonal(&puzzle_input));
+        // the default behavior is unchanged
+        assert_eq!(Answer::Int(31), solve_pt1(puzzle_input)?);
+
+        // diagonal steps still obey the climbing rule
+        assert_eq!(u32::MAX, solve_pt1_diagonal("Sz\nzz\nzE\n"));
 
         Ok(())
     }
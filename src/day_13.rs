@@ -1,38 +1,21 @@
-use std::{error::Error, fmt::Display, fs::File, io::Read, time::Instant};
+use std::{
+    cmp::Ordering,
+    error::Error,
+    fmt::Display,
+    iter::Peekable,
+    str::{CharIndices, FromStr},
+};
 
-use log::info;
+use log::debug;
+use serde::{Deserialize, Serialize};
 
-use crate::ProblemPart;
+use crate::Answer;
 
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
+solve_day!(solve_pt1, solve_pt2);
 
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
-        }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
-}
-
-#[derive(Debug, PartialEq, Clone)]
-enum PacketElement {
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PacketElement {
     Num(u32),
     Pack(Packet),
 }
@@ -46,68 +29,219 @@ impl Display for PacketElement {
     }
 }
 
-#[derive(Debug, Clone)]
-struct Packet {
+/// a packet is serialized as the json array of its content,
+/// that is the same textual form of the puzzle input
+///
+/// parsing, comparing, cloning, displaying and dropping a packet keep the
+/// nested packets on an explicit stack, hence deeply nested packets cannot
+/// overflow the call stack. Serde is recursive instead, and serde_json
+/// refuses to deserialize more than 128 levels
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Packet {
     content: Vec<PacketElement>,
 }
 
 impl Display for Packet {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[").unwrap();
-        for el in self.content.iter() {
-            write!(f, "{el},").unwrap();
+        write!(f, "[")?;
+        let mut stack = vec![self.content.iter()];
+        while let Some(iter) = stack.last_mut() {
+            match iter.next() {
+                Some(PacketElement::Num(num)) => write!(f, "{num},")?,
+                Some(PacketElement::Pack(packet)) => {
+                    write!(f, "[")?;
+                    stack.push(packet.content.iter());
+                }
+                None => {
+                    write!(f, "]")?;
+                    stack.pop();
+                    // the nested packet is an element of its parent
+                    if !stack.is_empty() {
+                        write!(f, ",")?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Packet({self})")
+    }
+}
+
+impl Clone for Packet {
+    fn clone(&self) -> Self {
+        // each level keeps the elements still to clone and the ones already cloned
+        let mut stack = vec![(self.content.iter(), Vec::with_capacity(self.content.len()))];
+        loop {
+            let (iter, cloned) = stack.last_mut().unwrap();
+            match iter.next() {
+                Some(PacketElement::Num(num)) => cloned.push(PacketElement::Num(*num)),
+                Some(PacketElement::Pack(packet)) => stack.push((
+                    packet.content.iter(),
+                    Vec::with_capacity(packet.content.len()),
+                )),
+                None => {
+                    let (_, content) = stack.pop().unwrap();
+                    let packet = Packet { content };
+                    match stack.last_mut() {
+                        Some((_, parent)) => parent.push(PacketElement::Pack(packet)),
+                        None => return packet,
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Packet {
+    fn drop(&mut self) {
+        // the nested packets are emptied before being dropped, hence
+        // their own drop does not go any deeper
+        let mut stack: Vec<Packet> = Vec::new();
+        let mut content = std::mem::take(&mut self.content);
+        loop {
+            for el in content.drain(..) {
+                if let PacketElement::Pack(packet) = el {
+                    stack.push(packet);
+                }
+            }
+            match stack.pop() {
+                Some(mut packet) => content = std::mem::take(&mut packet.content),
+                None => break,
+            }
+        }
+    }
+}
+
+/// error raised when a packet cannot be parsed, it keeps the
+/// byte offset of the input where the problem has been found
+#[derive(Debug, PartialEq)]
+pub struct ParsePacketError {
+    offset: usize,
+    message: String,
+}
+
+impl Display for ParsePacketError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+impl Error for ParsePacketError {}
+
+impl FromStr for Packet {
+    type Err = ParsePacketError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.char_indices().peekable();
+        if chars.peek().map(|(_, c)| *c) != Some('[') {
+            return Err(ParsePacketError {
+                offset: 0,
+                message: "expected opening bracket".to_string(),
+            });
+        }
+        let packet = Packet::from_chars(&mut chars, s.len())?;
+        if let Some((offset, c)) = chars.next() {
+            return Err(ParsePacketError {
+                offset,
+                message: format!("unexpected character '{c}' after the packet"),
+            });
         }
-        write!(f, "]")
+        Ok(packet)
     }
 }
 
 impl Packet {
-    fn from_string(input: &str) -> (usize, Packet) {
-        let mut content: Vec<PacketElement> = Vec::new();
-        let mut elems = 0;
-        let mut content_iter = input.chars().skip(1).enumerate();
-        // by scanning chars we ignore numbers with more than one digits
-        // therefore, we save the chars to this variable and whenever we
-        // read [ ] or , we close the number and we add it to the content list
-        let mut num_to_build = String::new();
-        while let Some((i, el)) = content_iter.next() {
-            if let Some(num) = el.to_digit(10) {
-                //content.push(PacketElement::Num(num));
-                num_to_build.push_str(&num.to_string());
-            } else if el == '[' {
-                if !num_to_build.is_empty() {
-                    content.push(PacketElement::Num(num_to_build.parse::<u32>().unwrap()));
-                    num_to_build = String::new();
+    /// builds the packet consuming the chars from the opening bracket
+    /// to the matching closing one
+    ///
+    /// nested packets are kept on an explicit stack instead of recursing,
+    /// hence deeply nested inputs cannot overflow the call stack
+    fn from_chars(
+        chars: &mut Peekable<CharIndices>,
+        input_len: usize,
+    ) -> Result<Packet, ParsePacketError> {
+        let mut stack: Vec<Vec<PacketElement>> = Vec::new();
+        loop {
+            match chars.peek() {
+                Some(&(offset, el)) if el.is_ascii_digit() => {
+                    // numbers can have more than one digit so we read
+                    // all of them before closing the number
+                    let mut num_to_build = String::new();
+                    while let Some((_, digit)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                        num_to_build.push(digit);
+                    }
+                    let num = num_to_build.parse::<u32>().map_err(|_| ParsePacketError {
+                        offset,
+                        message: format!("invalid number {num_to_build}"),
+                    })?;
+                    // the caller guarantees that we start from an opening bracket
+                    // and we return on the last closing one, so a list is open
+                    stack.last_mut().unwrap().push(PacketElement::Num(num));
                 }
-                let (n, pack) = Packet::from_string(&input[i + 1..]);
-                content.push(PacketElement::Pack(pack));
-                // we skip the number of chars that composed the created packet
-                for _ in 0..=n {
-                    content_iter.next();
+                Some((_, '[')) => {
+                    chars.next();
+                    stack.push(Vec::new());
                 }
-            } else if el == ']' {
-                if !num_to_build.is_empty() {
-                    content.push(PacketElement::Num(num_to_build.parse::<u32>().unwrap()));
-                    // useless because we close the loop after
-                    //num_to_build = String::new();
+                Some((_, ']')) => {
+                    chars.next();
+                    let packet = Packet {
+                        content: stack.pop().unwrap(),
+                    };
+                    match stack.last_mut() {
+                        Some(parent) => parent.push(PacketElement::Pack(packet)),
+                        None => return Ok(packet),
+                    }
+                }
+                Some((_, ',')) => {
+                    chars.next();
                 }
-                elems = i + 1;
-                break;
-            } else {
-                // we read a comma
-                if !num_to_build.is_empty() {
-                    content.push(PacketElement::Num(num_to_build.parse::<u32>().unwrap()));
-                    num_to_build = String::new();
+                Some(&(offset, c)) => {
+                    return Err(ParsePacketError {
+                        offset,
+                        message: format!("unexpected character '{c}'"),
+                    })
+                }
+                None => {
+                    return Err(ParsePacketError {
+                        offset: input_len,
+                        message: "missing closing bracket".to_string(),
+                    })
                 }
             }
         }
-        (elems, Packet { content })
     }
 }
 
 impl PartialEq for Packet {
     fn eq(&self, other: &Self) -> bool {
-        self.content.eq(&other.content)
+        let mut stack = vec![(self.content.iter(), other.content.iter())];
+        while let Some((self_iter, other_iter)) = stack.last_mut() {
+            match (self_iter.next(), other_iter.next()) {
+                (None, None) => {
+                    stack.pop();
+                }
+                (Some(PacketElement::Num(self_num)), Some(PacketElement::Num(other_num))) => {
+                    if self_num != other_num {
+                        return false;
+                    }
+                }
+                (
+                    Some(PacketElement::Pack(self_packet)),
+                    Some(PacketElement::Pack(other_packet)),
+                ) => {
+                    stack.push((self_packet.content.iter(), other_packet.content.iter()));
+                }
+                // different lengths or a number against a list
+                _ => return false,
+            }
+        }
+        true
     }
 }
 
@@ -120,15 +254,22 @@ impl PartialOrd for Packet {
 }
 
 impl Ord for Packet {
+    /// compares the packets walking both of them at the same time, the
+    /// lists being compared are kept on an explicit stack so that deeply
+    /// nested packets cannot overflow the call stack
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let mut self_iter = self.content.iter();
-        let mut other_iter = other.content.iter();
-
-        let mut self_elem = self_iter.next();
-        let mut other_elem = other_iter.next();
+        let mut stack = vec![(self.content.iter(), other.content.iter())];
 
-        while other_elem.is_some() & self_elem.is_some() {
-            match (self_elem, other_elem) {
+        while let Some((self_iter, other_iter)) = stack.last_mut() {
+            match (self_iter.next(), other_iter.next()) {
+                // both lists run out of items at the same time so they are
+                // equal and we continue checking the parent lists
+                (None, None) => {
+                    stack.pop();
+                }
+                // if right side run out of items then self is greater than it
+                (Some(_), None) => return std::cmp::Ordering::Greater,
+                (None, Some(_)) => return std::cmp::Ordering::Less,
                 (Some(PacketElement::Num(self_num)), Some(PacketElement::Num(other_num))) => {
                     match self_num.cmp(other_num) {
                         std::cmp::Ordering::Equal => {}
@@ -140,50 +281,29 @@ impl Ord for Packet {
                     Some(PacketElement::Pack(self_packet)),
                     Some(PacketElement::Pack(other_packet)),
                 ) => {
-                    let cmp_result = self_packet.cmp(other_packet);
-                    if cmp_result != std::cmp::Ordering::Equal {
-                        return cmp_result;
-                    }
-                    // they are equal so we continue checking
+                    stack.push((self_packet.content.iter(), other_packet.content.iter()));
                 }
+                // a number compared with a list is treated as a list
+                // containing only that number
                 (Some(self_num), Some(PacketElement::Pack(other_packet))) => {
-                    let self_packet = Packet {
-                        content: vec![(*self_num).clone()],
-                    };
-                    let cmp_result = self_packet.cmp(other_packet);
-                    if cmp_result != std::cmp::Ordering::Equal {
-                        return cmp_result;
-                    }
-                    // they are equal so we continue checking
+                    stack.push((
+                        std::slice::from_ref(self_num).iter(),
+                        other_packet.content.iter(),
+                    ));
                 }
                 (Some(PacketElement::Pack(self_packet)), Some(other_num)) => {
-                    let other_packet = Packet {
-                        content: vec![(*other_num).clone()],
-                    };
-                    let cmp_result = self_packet.cmp(&other_packet);
-                    if cmp_result != std::cmp::Ordering::Equal {
-                        return cmp_result;
-                    }
-                    // they are equal so we continue checking
+                    stack.push((
+                        self_packet.content.iter(),
+                        std::slice::from_ref(other_num).iter(),
+                    ));
                 }
-                (_, _) => break,
             }
-            self_elem = self_iter.next();
-            other_elem = other_iter.next();
-        }
-
-        // if right side run out of tiems then self is greater than it
-        if other_elem.is_none() & self_elem.is_some() {
-            std::cmp::Ordering::Greater
-        } else if other_elem.is_some() & self_elem.is_none() {
-            std::cmp::Ordering::Less
-        } else {
-            std::cmp::Ordering::Equal
         }
+        std::cmp::Ordering::Equal
     }
 }
 
-fn parse_input(puzzle_input: String) -> Vec<(Packet, Packet)> {
+fn parse_input(puzzle_input: String) -> Result<Vec<(Packet, Packet)>, Box<dyn Error>> {
     let mut pairs = Vec::new();
 
     for group in puzzle_input
@@ -192,56 +312,83 @@ fn parse_input(puzzle_input: String) -> Vec<(Packet, Packet)> {
         .collect::<Vec<&str>>()
         .chunks(2)
     {
-        let first = Packet::from_string(group[0]).1;
-        let second = Packet::from_string(group[1]).1;
+        let first = Packet::from_str(group[0])?;
+        let second = Packet::from_str(group[1])?;
         pairs.push((first, second));
     }
-    pairs
+    Ok(pairs)
 }
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let pairs = parse_input(puzzle_input);
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let pairs = parse_input(puzzle_input)?;
     let mut right_order_pairs = Vec::new();
     for (i, (left, right)) in pairs.iter().enumerate() {
         if left < right {
-            println!("\n\nLEFT\n{}", left);
-            println!("RIGHT\n{}", right);
+            debug!("\n\nLEFT\n{}", left);
+            debug!("RIGHT\n{}", right);
             right_order_pairs.push(i as i32 + 1);
             let _ = left.cmp(right);
         }
     }
-    Ok(right_order_pairs.iter().sum::<i32>().to_string())
+    Ok(Answer::from(right_order_pairs.iter().sum::<i32>()))
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let pairs = parse_input(puzzle_input);
-    let start_divider = Packet {
-        content: vec![PacketElement::Pack(Packet {
-            content: vec![PacketElement::Num(2)],
-        })],
-    };
-    let end_divider = Packet {
+/// compares two packets following the rules of the distress signal
+pub fn compare(a: &Packet, b: &Packet) -> Ordering {
+    a.cmp(b)
+}
+
+/// builds the divider packet `[[n]]`
+fn divider(n: u32) -> Packet {
+    Packet {
         content: vec![PacketElement::Pack(Packet {
-            content: vec![PacketElement::Num(6)],
+            content: vec![PacketElement::Num(n)],
         })],
-    };
-    let mut packets: Vec<Packet> = vec![start_divider.clone(), end_divider.clone()];
-    for (left, right) in pairs {
-        packets.push(left);
-        packets.push(right);
     }
-    packets.sort();
+}
 
-    let start_divider_index = packets.iter().position(|x| *x == start_divider).unwrap();
-    let end_divider_index = packets.iter().position(|x| *x == end_divider).unwrap();
-    Ok(((start_divider_index + 1) * (end_divider_index + 1)).to_string())
+/// sorts the packets together with the two divider packets and returns
+/// the product of the dividers positions, starting from 1
+pub fn decoder_key(packets: &[Packet]) -> usize {
+    decoder_key_with(packets.to_vec(), &[divider(2), divider(6)])
+}
+
+/// sorts the packets together with the given dividers and returns
+/// the product of the dividers positions, starting from 1
+pub fn decoder_key_with(pairs: Vec<Packet>, dividers: &[Packet]) -> usize {
+    let mut packets = pairs;
+    packets.extend_from_slice(dividers);
+    packets.sort_by(compare);
+
+    dividers
+        .iter()
+        .map(|divider| packets.iter().position(|x| x == divider).unwrap() + 1)
+        .product()
+}
+
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let pairs = parse_input(puzzle_input)?;
+    let packets: Vec<Packet> = pairs
+        .into_iter()
+        .flat_map(|(left, right)| [left, right])
+        .collect();
+
+    Ok(Answer::from(decoder_key_with(
+        packets,
+        &[divider(2), divider(6)],
+    )))
 }
 
 #[cfg(test)]
 mod test {
-    use std::{error::Error, fs::File, io::Read};
+    use std::{cmp::Ordering, error::Error, fs::File, io::Read, str::FromStr};
+
+    use crate::Answer;
 
-    use super::{solve_pt1, solve_pt2};
+    use super::{
+        compare, decoder_key, decoder_key_with, solve_pt1, solve_pt2, Packet, PacketElement,
+        ParsePacketError,
+    };
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -250,7 +397,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!("13".to_string(), result);
+        assert_eq!(Answer::Int(13), result);
 
         Ok(())
     }
@@ -262,8 +409,168 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!("140".to_string(), result);
+        assert_eq!(Answer::Int(140), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_digit_nested() {
+        let packet = Packet::from_str("[[12,3],[45],[[10]],7]").unwrap();
+        let expected = Packet {
+            content: vec![
+                PacketElement::Pack(Packet {
+                    content: vec![PacketElement::Num(12), PacketElement::Num(3)],
+                }),
+                PacketElement::Pack(Packet {
+                    content: vec![PacketElement::Num(45)],
+                }),
+                PacketElement::Pack(Packet {
+                    content: vec![PacketElement::Pack(Packet {
+                        content: vec![PacketElement::Num(10)],
+                    })],
+                }),
+                PacketElement::Num(7),
+            ],
+        };
+
+        assert_eq!(expected, packet);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Err(ParsePacketError {
+                offset: 5,
+                message: "missing closing bracket".to_string()
+            }),
+            Packet::from_str("[1,2,")
+        );
+        assert_eq!(
+            Err(ParsePacketError {
+                offset: 0,
+                message: "expected opening bracket".to_string()
+            }),
+            Packet::from_str("1,2]")
+        );
+        assert!(Packet::from_str("[1,a]").is_err());
+        assert!(Packet::from_str("[1]]").is_err());
+        assert!(solve_pt1("[1,2,\n[1]\n".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_serde_round_trip() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_13_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        for line in puzzle_input.lines().filter(|line| !line.is_empty()) {
+            let packet = Packet::from_str(line)?;
+            let json = serde_json::to_string(&packet)?;
+
+            assert_eq!(line, json);
+            assert_eq!(packet, serde_json::from_str::<Packet>(&json)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cmp_edge_cases() -> Result<(), Box<dyn Error>> {
+        let cases = [
+            ("[]", "[]", Ordering::Equal),
+            ("[]", "[1]", Ordering::Less),
+            ("[1]", "[]", Ordering::Greater),
+            ("[]", "[[]]", Ordering::Less),
+            ("[[]]", "[]", Ordering::Greater),
+            ("[[]]", "[[]]", Ordering::Equal),
+            ("[[]]", "[1]", Ordering::Less),
+            ("[1]", "[[]]", Ordering::Greater),
+            ("[1,1]", "[1,1,1]", Ordering::Less),
+            ("[1,1,1]", "[1,1]", Ordering::Greater),
+            ("[1]", "[[1]]", Ordering::Equal),
+            ("[[1],1]", "[1,[1],0]", Ordering::Less),
+            ("[[[]]]", "[[]]", Ordering::Greater),
+        ];
+        for (left, right, ordering) in cases {
+            let left = Packet::from_str(left)?;
+            let right = Packet::from_str(right)?;
+
+            assert_eq!(ordering, left.cmp(&right), "{left} vs {right}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decoder_key() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_13_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let packets = puzzle_input
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(Packet::from_str)
+            .collect::<Result<Vec<Packet>, ParsePacketError>>()?;
+
+        assert_eq!(140, decoder_key(&packets));
+        assert_eq!(Ordering::Less, compare(&packets[0], &packets[1]));
+        assert_eq!(Ordering::Greater, compare(&packets[4], &packets[5]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decoder_key_with() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_13_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let packets = puzzle_input
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(Packet::from_str)
+            .collect::<Result<Vec<Packet>, ParsePacketError>>()?;
+
+        let standard = [Packet::from_str("[[2]]")?, Packet::from_str("[[6]]")?];
+        assert_eq!(140, decoder_key_with(packets.clone(), &standard));
+
+        // any number of dividers is allowed, [[10]] sorts last
+        let dividers = [
+            Packet::from_str("[[1]]")?,
+            Packet::from_str("[5]")?,
+            Packet::from_str("[[10]]")?,
+        ];
+        assert_eq!(4 * 14 * 19, decoder_key_with(packets, &dividers));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deeply_nested() -> Result<(), Box<dyn Error>> {
+        let depth = 200_000;
+        let deep = format!("{}1{}", "[".repeat(depth), "]".repeat(depth));
+        let deeper = format!("{}2{}", "[".repeat(depth), "]".repeat(depth));
+        let left = Packet::from_str(&deep)?;
+        let right = Packet::from_str(&deeper)?;
+
+        assert_eq!(Ordering::Less, left.cmp(&right));
+        assert_eq!(Ordering::Greater, right.cmp(&left));
+        assert_eq!(Ordering::Equal, left.cmp(&Packet::from_str("[1]")?));
+        assert_eq!(Ordering::Less, left.cmp(&Packet::from_str("[[1],2]")?));
+        assert!(Packet::from_str(&deep[1..]).is_err());
+
+        let copy = left.clone();
+        assert!(copy == left);
+        assert!(copy != right);
+        assert!(copy != Packet::from_str("[1]")?);
+
+        assert_eq!("[[1,],]", Packet::from_str("[[1]]")?.to_string());
+        assert_eq!(
+            format!("{}1,{}]", "[".repeat(depth), "],".repeat(depth - 1)),
+            copy.to_string()
+        );
 
+        // all the packets are dropped here
         Ok(())
     }
 }
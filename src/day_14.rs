@@ -1,41 +1,11 @@
 use std::{
     collections::{HashMap, HashSet},
     error::Error,
-    fs::File,
-    io::Read,
-    time::Instant,
 };
 
-use log::info;
+use crate::Answer;
 
-use crate::ProblemPart;
-
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
-
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
-        }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
-}
+solve_day!(solve_pt1, solve_pt2);
 
 fn parse_pair(pair: &str) -> (u32, u32) {
     let mut elems = pair.split(',');
@@ -95,25 +65,26 @@ fn fall(
     floor: &HashMap<u32, Vec<u32>>,
     starting_position: &(u32, u32),
 ) -> Option<(u32, u32)> {
-    if starting_position.1 == 0 {
-        // since we reached the extreme left the sand unit will fall forever
-        None
-    } else if let Some(Some(&center)) = floor
-        .get(&starting_position.1)
-        .map(|centers| centers.iter().filter(|&&c| c > starting_position.0).min())
-    {
-        if !scan.contains(&(center, starting_position.1 - 1)) {
+    let mut position = *starting_position;
+    loop {
+        if position.1 == 0 {
+            // since we reached the extreme left the sand unit will fall forever
+            return None;
+        }
+        // if there is no floor then the sand will fall forever
+        let &center = floor
+            .get(&position.1)
+            .and_then(|centers| centers.iter().filter(|&&c| c > position.0).min())?;
+
+        if !scan.contains(&(center, position.1 - 1)) {
             // the left is empty so the sand unit goes there and then we check the fall
-            fall(scan, floor, &(center, starting_position.1 - 1))
-        } else if !scan.contains(&(center, starting_position.1 + 1)) {
+            position = (center, position.1 - 1);
+        } else if !scan.contains(&(center, position.1 + 1)) {
             // the right is empty so the sand unit goes there and then we check the fall
-            fall(scan, floor, &(center, starting_position.1 + 1))
+            position = (center, position.1 + 1);
         } else {
-            Some((center - 1, starting_position.1))
+            return Some((center - 1, position.1));
         }
-    } else {
-        // if there is no floor then the sand will fall forever
-        None
     }
 }
 
@@ -123,25 +94,29 @@ fn fall_with_floor(
     starting_position: &(u32, u32),
     floor_row: u32,
 ) -> Option<(u32, u32)> {
-    if starting_position.1 == 0 {
-        // since we reached the extreme left the sand unit will fall forever
-        None
-    } else if let Some(Some(&center)) = floor
-        .get(&starting_position.1)
-        .map(|centers| centers.iter().filter(|&&c| c > starting_position.0).min())
-    {
-        if !scan.contains(&(center, starting_position.1 - 1)) {
+    let mut position = *starting_position;
+    loop {
+        if position.1 == 0 {
+            // since we reached the extreme left the sand unit will fall forever
+            return None;
+        }
+        let Some(&center) = floor
+            .get(&position.1)
+            .and_then(|centers| centers.iter().filter(|&&c| c > position.0).min())
+        else {
+            // if there is no floor we hit the actual floor
+            return Some((floor_row - 1, position.1));
+        };
+
+        if !scan.contains(&(center, position.1 - 1)) {
             // the left is empty so the sand unit goes there and then we check the fall
-            fall_with_floor(scan, floor, &(center, starting_position.1 - 1), floor_row)
-        } else if !scan.contains(&(center, starting_position.1 + 1)) {
+            position = (center, position.1 - 1);
+        } else if !scan.contains(&(center, position.1 + 1)) {
             // the right is empty so the sand unit goes there and then we check the fall
-            fall_with_floor(scan, floor, &(center, starting_position.1 + 1), floor_row)
+            position = (center, position.1 + 1);
         } else {
-            Some((center - 1, starting_position.1))
+            return Some((center - 1, position.1));
         }
-    } else {
-        // if there is no floor we hit the actual floor
-        Some((floor_row - 1, starting_position.1))
     }
 }
 
@@ -164,39 +139,31 @@ fn _print_scan(rocks_scan: &HashSet<(u32, u32)>, full_scan: &HashSet<(u32, u32)>
     }
 }
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let (mut scan, mut floor) = parse_input(puzzle_input);
-    let mut sands_unit = 0;
+/// pours sand from the source, given as (row, column), until it starts
+/// falling forever or until the source is blocked.
+///
+/// Without the floor a sand unit falls forever as soon as it goes past
+/// the lowest rock of its column, with the floor the pouring continues
+/// until a unit settles on the source
+///
+/// returns the cells occupied by the settled sand units
+fn pour(puzzle_input: &str, source: (u32, u32), with_floor: bool) -> HashSet<(u32, u32)> {
+    let (mut scan, mut floor) = parse_input(puzzle_input.to_string());
+    let mut sand: HashSet<(u32, u32)> = HashSet::new();
+    let floor_row = scan.iter().map(|x| x.0).max().unwrap() + 2;
 
-    let source_col = 500;
-    let source_row = 0;
+    let (source_row, source_col) = source;
 
     loop {
-        let final_position = fall(&scan, &floor, &(source_row, source_col));
-        if let Some(final_position) = final_position {
-            floor.entry(final_position.1).and_modify(|x| {
-                x.push(final_position.0);
-            });
-            scan.insert(final_position);
-            sands_unit += 1;
-        } else {
+        if scan.contains(&(source_row, source_col)) {
+            // the source is occupied so no more sand can come out of it
             break;
         }
-    }
-    Ok(sands_unit.to_string())
-}
-
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let (mut scan, mut floor) = parse_input(puzzle_input);
-    //print_scan(&rock_scan, &scan);
-    let mut sands_unit = 0;
-    let floor_row = scan.iter().map(|x| x.0).max().unwrap() + 2;
-
-    let source_col = 500;
-    let source_row = 0;
-
-    loop {
-        let final_position = fall_with_floor(&scan, &floor, &(source_row, source_col), floor_row);
+        let final_position = if with_floor {
+            fall_with_floor(&scan, &floor, &(source_row, source_col), floor_row)
+        } else {
+            fall(&scan, &floor, &(source_row, source_col))
+        };
 
         if let Some(final_position) = final_position {
             floor
@@ -206,23 +173,93 @@ fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
                 })
                 .or_insert(vec![final_position.0]);
             scan.insert(final_position);
-            sands_unit += 1;
+            sand.insert(final_position);
             //print_scan(&rock_scan, &scan);
-            if final_position == (source_row, source_col) {
-                break;
-            }
         } else {
             break;
         }
     }
-    Ok(sands_unit.to_string())
+    sand
+}
+
+/// returns the cells occupied by the settled sand units
+/// when the sand is poured from the usual source at column 500
+pub fn resting_sand(puzzle_input: &str, with_floor: bool) -> HashSet<(u32, u32)> {
+    pour(puzzle_input, (0, 500), with_floor)
+}
+
+/// returns the number of sand units that settle without the floor
+/// when the sand is poured from the given source, as (row, column)
+pub fn solve_pt1_source(puzzle_input: &str, source: (u32, u32)) -> usize {
+    pour(puzzle_input, source, false).len()
+}
+
+/// returns, for each column, how many sand units settled in it
+pub fn column_heights(puzzle_input: &str, with_floor: bool) -> HashMap<u32, u32> {
+    let mut heights: HashMap<u32, u32> = HashMap::new();
+    for (_, c) in resting_sand(puzzle_input, with_floor) {
+        *heights.entry(c).or_insert(0) += 1;
+    }
+    heights
+}
+
+/// returns the final cave as rows of chars, cropped to the bounding box
+/// of rocks, sand and source: `#` is rock, `o` is sand, `.` is air and
+/// `+` is the source, unless a sand unit settled on it
+///
+/// with the floor, its row is included as rock across the whole width
+pub fn to_grid(puzzle_input: &str, with_floor: bool) -> Vec<Vec<char>> {
+    let (rocks, _) = parse_input(puzzle_input.to_string());
+    let sand = resting_sand(puzzle_input, with_floor);
+    let source = (0, 500);
+
+    let occupied = || rocks.iter().chain(sand.iter()).chain([&source]);
+    let min_c = occupied().map(|x| x.1).min().unwrap();
+    let max_c = occupied().map(|x| x.1).max().unwrap();
+    let max_r = rocks.iter().map(|x| x.0).max().unwrap();
+    let floor_row = max_r + 2;
+    let max_r = if with_floor {
+        floor_row
+    } else {
+        occupied().map(|x| x.0).max().unwrap()
+    };
+
+    (0..=max_r)
+        .map(|r| {
+            (min_c..=max_c)
+                .map(|c| {
+                    if rocks.contains(&(r, c)) || (with_floor && r == floor_row) {
+                        '#'
+                    } else if sand.contains(&(r, c)) {
+                        'o'
+                    } else if (r, c) == source {
+                        '+'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    Ok(Answer::from(resting_sand(&puzzle_input, false).len()))
+}
+
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    Ok(Answer::from(resting_sand(&puzzle_input, true).len()))
 }
 
 #[cfg(test)]
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use crate::Answer;
+
+    use super::{
+        column_heights, pour, resting_sand, solve_pt1, solve_pt1_source, solve_pt2, to_grid,
+    };
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -231,7 +268,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!("24".to_string(), result);
+        assert_eq!(Answer::Int(24), result);
         Ok(())
     }
 
@@ -242,8 +279,105 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!("93".to_string(), result);
+        assert_eq!(Answer::Int(93), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pt1_deep_fall() -> Result<(), Box<dyn Error>> {
+        // a long staircase where the first sand unit slides on each step
+        // before falling forever, with recursion this overflowed the stack
+        let puzzle_input: String = (1..=100_000u32)
+            .map(|k| format!("{},{k} -> {},{k}\n", 498 + k, 499 + k))
+            .collect();
+        let result = solve_pt1(puzzle_input)?;
+
+        assert_eq!(Answer::Int(0), result);
+        Ok(())
+    }
+
+    #[test]
+    fn test_resting_sand() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_14_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let sand = resting_sand(&puzzle_input, false);
+
+        assert_eq!(24, sand.len());
+        // the first unit rests on the rock below the source
+        assert!(sand.contains(&(8, 500)));
+        assert_eq!(93, resting_sand(&puzzle_input, true).len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_source() -> Result<(), Box<dyn Error>> {
+        let puzzle_input = "18,4 -> 22,4\n15,7 -> 25,7\n";
+
+        assert_eq!(8, solve_pt1_source(puzzle_input, (0, 20)));
+        assert_eq!(52, pour(puzzle_input, (0, 20), true).len());
+        // the source is closer to the rocks so it is blocked earlier
+        assert_eq!(4, pour(puzzle_input, (2, 20), true).len());
+
+        let mut file = File::open("inputs/day_14_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!(24, solve_pt1_source(&puzzle_input, (0, 500)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_column_heights() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_14_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let heights = column_heights(&puzzle_input, false);
+
+        assert_eq!(Some(&7), heights.get(&500));
+        assert_eq!(Some(&6), heights.get(&499));
+        assert_eq!(None, heights.get(&494));
+        assert_eq!(24, heights.values().sum::<u32>());
+
+        let heights = column_heights(&puzzle_input, true);
+
+        assert_eq!(Some(&9), heights.get(&500));
+        assert_eq!(93, heights.values().sum::<u32>());
+        Ok(())
+    }
+
+    #[test]
+    fn test_blocked_source() {
+        // the first unit cannot move and settles on the source
+        assert_eq!(1, solve_pt1_source("499,1 -> 501,1\n", (0, 500)));
+        assert_eq!(1, pour("499,1 -> 501,1\n", (0, 500), true).len());
+        // the source is a rock so no sand comes out
+        assert_eq!(0, solve_pt1_source("499,0 -> 501,0\n", (0, 500)));
+    }
+
+    #[test]
+    fn test_to_grid() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_14_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        let grid = to_grid(&puzzle_input, false);
+        let sand = grid.iter().flatten().filter(|&&x| x == 'o').count();
+
+        assert_eq!(Answer::from(sand), solve_pt1(puzzle_input.clone())?);
+        assert_eq!(10, grid.len());
+        assert_eq!("......+...", grid[0].iter().collect::<String>());
+        assert_eq!("....#ooo##", grid[4].iter().collect::<String>());
+        assert_eq!("#########.", grid[9].iter().collect::<String>());
+
+        let grid = to_grid(&puzzle_input, true);
+        let sand = grid.iter().flatten().filter(|&&x| x == 'o').count();
 
+        assert_eq!(Answer::from(sand), solve_pt2(puzzle_input)?);
+        // the last unit settles on the source
+        assert_eq!(Some(&'o'), grid[0].iter().find(|&&x| x != '.'));
+        assert!(grid[11].iter().all(|&x| x == '#'));
         Ok(())
     }
 }
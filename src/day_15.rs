@@ -1,43 +1,53 @@
-use std::{collections::HashSet, error::Error, fs::File, io::Read, time::Instant};
+use std::{collections::HashSet, error::Error, time::Instant};
 
 use log::info;
-use regex::Regex;
+use rayon::prelude::*;
+use regex::{Captures, Regex};
 
-use crate::ProblemPart;
+use crate::{error::AocError, utils::load_input, Answer, ProblemPart};
 
 pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
+    let puzzle_input = load_input(puzzle_input)?;
+    let result = solve_str(&puzzle_input, part)?;
+    info!("Problem solution is {}", result);
+    Ok(())
+}
+
+/// solves the part of the problem on the puzzle input given as a string
+pub fn solve_str(puzzle_input: &str, part: ProblemPart) -> Result<Answer, Box<dyn Error>> {
+    let puzzle_input = puzzle_input.to_string();
 
     let result = match part {
         ProblemPart::One => {
             info!("Start solving part 1");
             let start = Instant::now();
             let result = solve_pt1(puzzle_input, 2000000)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
+            let duration = start.elapsed().as_millis();
+            info!("Solved part 1 in {duration} milli seconds.");
             result
         }
         ProblemPart::Two => {
             info!("Start solving part 2");
             let start = Instant::now();
             let result = solve_pt2(puzzle_input, 4000000)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
+            let duration = start.elapsed().as_millis();
+            info!("Solved part 2 in {duration} milli seconds.");
             result
         }
+        ProblemPart::Both => Answer::Both(
+            Box::new(solve_str(&puzzle_input, ProblemPart::One)?),
+            Box::new(solve_str(&puzzle_input, ProblemPart::Two)?),
+        ),
     };
-    info!("Problem solution is {}", result);
-    Ok(())
+    Ok(result)
 }
 
-fn manhattan_distance(left: &(i32, i32), right: &(i32, i32)) -> i32 {
+fn manhattan_distance(left: &(i64, i64), right: &(i64, i64)) -> i64 {
     (left.0 - right.0).abs() + (left.1 - right.1).abs()
 }
 
 /// returns the upper and lower bounds for x
-fn inner_points(sensor: &(i32, i32, i32), y: i32) -> Option<(i32, i32)> {
+fn inner_points(sensor: &(i64, i64, i64), y: i64) -> Option<(i64, i64)> {
     /*
     |sx - x| + (sy - y) <= r
 
@@ -64,39 +74,37 @@ fn inner_points(sensor: &(i32, i32, i32), y: i32) -> Option<(i32, i32)> {
     }
 }
 
-type Sensors = Vec<(i32, i32, i32)>;
-type Beacons = HashSet<(i32, i32)>;
+/// each sensor is represented by its coordinates and the distance of its beacon
+pub type Sensors = Vec<(i64, i64, i64)>;
+type Beacons = HashSet<(i64, i64)>;
 
-fn parse_input(puzzle_input: String) -> (Sensors, Beacons) {
-    let mut sensors: Vec<(i32, i32, i32)> = Vec::new();
-    let mut beacons: HashSet<(i32, i32)> = HashSet::new();
+fn parse_input(puzzle_input: String) -> Result<(Sensors, Beacons), AocError> {
+    let mut sensors: Vec<(i64, i64, i64)> = Vec::new();
+    let mut beacons: HashSet<(i64, i64)> = HashSet::new();
     let re = Regex::new(r"x=(?P<x>-?\d+), y=(?P<y>-?\d+)").unwrap();
-    for line in puzzle_input.lines() {
+    for (i, line) in puzzle_input.lines().enumerate() {
+        let parse_coordinate = |capture: &Captures, name: &str| {
+            capture[name].parse::<i64>().map_err(|error| {
+                AocError::parse(15, i + 1, format!("{name}={}: {error}", &capture[name]))
+            })
+        };
         let mut re_iter = re.captures_iter(line);
 
-        let sensor_capture = re_iter.next().unwrap();
-        let beacon_capture = re_iter.next().unwrap();
+        let (Some(sensor_capture), Some(beacon_capture)) = (re_iter.next(), re_iter.next()) else {
+            return Err(AocError::parse(
+                15,
+                i + 1,
+                format!("expected the sensor and the beacon coordinates in '{line}'"),
+            ));
+        };
 
         let sensor = (
-            sensor_capture
-                .name("x")
-                .map(|m| m.as_str().parse::<i32>().unwrap())
-                .unwrap(),
-            sensor_capture
-                .name("y")
-                .map(|m| m.as_str().parse::<i32>().unwrap())
-                .unwrap(),
+            parse_coordinate(&sensor_capture, "x")?,
+            parse_coordinate(&sensor_capture, "y")?,
         );
-
         let beacon = (
-            beacon_capture
-                .name("x")
-                .map(|m| m.as_str().parse::<i32>().unwrap())
-                .unwrap(),
-            beacon_capture
-                .name("y")
-                .map(|m| m.as_str().parse::<i32>().unwrap())
-                .unwrap(),
+            parse_coordinate(&beacon_capture, "x")?,
+            parse_coordinate(&beacon_capture, "y")?,
         );
 
         let distance = manhattan_distance(&sensor, &beacon);
@@ -104,100 +112,162 @@ fn parse_input(puzzle_input: String) -> (Sensors, Beacons) {
         sensors.push((sensor.0, sensor.1, distance));
     }
 
-    (sensors, beacons)
+    Ok((sensors, beacons))
 }
 
-fn overlaps(left: &(i32, i32), right: &(i32, i32)) -> bool {
-    (left.0 <= right.1) && (right.0 <= left.1)
-}
+/// sorts the inclusive ranges and coalesces the ones that overlap or touch
+pub fn merge_ranges(mut ranges: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+    ranges.sort_by_key(|a| a.0);
 
-fn solve_pt1(puzzle_input: String, y: i32) -> Result<String, Box<dyn Error>> {
-    let (sensors, beacons) = parse_input(puzzle_input);
-    let mut bounds = sensors
-        .iter()
-        .filter_map(|s| inner_points(s, y))
-        .collect::<Vec<(i32, i32)>>();
-
-    bounds.sort_by(|a, b| a.0.cmp(&b.0));
-
-    let mut ranges: Vec<(i32, i32)> = vec![*bounds.first().unwrap()];
-    for bound in bounds.iter().skip(1) {
-        let last_range = ranges.last_mut().unwrap();
-        if overlaps(last_range, bound) {
-            last_range.0 = last_range.0.min(bound.0);
-            last_range.1 = last_range.1.max(bound.1);
-        } else {
-            ranges.push(*bound);
+    let mut merged: Vec<(i64, i64)> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            // ranges are sorted so the last one is extended when they overlap or touch
+            Some(last_range) if range.0 <= last_range.1 + 1 => {
+                last_range.1 = last_range.1.max(range.1);
+            }
+            _ => merged.push(range),
         }
     }
+    merged
+}
 
-    let mut contained_beacons = 0;
-    let y_beacons: Vec<&(i32, i32)> = beacons
+/// returns the sorted and merged ranges of x covered by the sensors on the given row
+pub fn covered_ranges(sensors: &Sensors, y: i64) -> Vec<(i64, i64)> {
+    merge_ranges(sensors.iter().filter_map(|s| inner_points(s, y)).collect())
+}
+
+/// returns the number of positions of the row where a beacon cannot be present
+fn excluded_in_row(sensors: &Sensors, beacons: &Beacons, y: i64) -> i64 {
+    let y_beacons: Vec<&(i64, i64)> = beacons
         .iter()
         .filter(|e| e.1 == y)
-        .collect::<Vec<&(i32, i32)>>();
-    for range in ranges {
-        let mut range_len = range.1 - range.0 + 1;
-        for beacon in y_beacons.iter() {
-            if overlaps(&range, beacon) {
-                range_len -= 1;
-            }
-            contained_beacons += range_len;
-        }
+        .collect::<Vec<&(i64, i64)>>();
+    let mut contained_beacons = 0;
+    for range in covered_ranges(sensors, y) {
+        // beacons are distinct, hence each one in the range is removed once
+        let range_beacons = y_beacons
+            .iter()
+            .filter(|beacon| (range.0 <= beacon.0) && (beacon.0 <= range.1))
+            .count() as i64;
+        contained_beacons += range.1 - range.0 + 1 - range_beacons;
     }
+    contained_beacons
+}
 
-    Ok(contained_beacons.to_string())
+/// returns, for each row from `y_from` to `y_to` included, the row
+/// and the number of positions where a beacon cannot be present
+pub fn excluded_counts(
+    puzzle_input: &str,
+    y_from: i64,
+    y_to: i64,
+) -> Result<Vec<(i64, i64)>, AocError> {
+    let (sensors, beacons) = parse_input(puzzle_input.to_string())?;
+
+    Ok((y_from..=y_to)
+        .map(|y| (y, excluded_in_row(&sensors, &beacons, y)))
+        .collect())
 }
 
-fn solve_pt2(puzzle_input: String, max_bound: i32) -> Result<String, Box<dyn Error>> {
-    let (sensors, _) = parse_input(puzzle_input);
+fn solve_pt1(puzzle_input: String, y: i64) -> Result<Answer, Box<dyn Error>> {
+    let (sensors, beacons) = parse_input(puzzle_input)?;
+    Ok(Answer::from(excluded_in_row(&sensors, &beacons, y)))
+}
 
-    for y in 0..=max_bound {
-        let mut bounds = sensors
-            .iter()
-            .filter_map(|s| inner_points(s, y))
-            .collect::<Vec<(i32, i32)>>();
-
-        bounds.sort_by(|a, b| a.0.cmp(&b.0));
-        let mut first = *bounds.first().unwrap();
-        first.0 = first.0.max(0);
-        first.1 = first.1.min(max_bound);
-        let mut ranges: Vec<(i32, i32)> = vec![first];
-        for bound in bounds.iter().skip(1) {
-            let last_range = ranges.last_mut().unwrap();
-            if overlaps(last_range, bound) {
-                last_range.0 = last_range.0.min(bound.0).max(0);
-                last_range.1 = last_range.1.max(bound.1).min(max_bound);
-            } else {
-                ranges.push(*bound);
-            }
+/// returns the first x of the row in `[0, max_bound]` that is not covered by the sensors
+fn row_gap(sensors: &Sensors, y: i64, max_bound: i64) -> Option<i64> {
+    // we look for the first x not covered by the ranges, starting from the left bound
+    let mut x = 0;
+    for range in covered_ranges(sensors, y) {
+        if range.0 > x {
+            break;
         }
+        x = x.max(range.1 + 1);
+    }
+    (x <= max_bound).then_some(x)
+}
 
-        let mut occupied_slots = 0;
-        for range in ranges.iter() {
-            occupied_slots += range.1 - range.0 + 1;
-        }
-        if occupied_slots == max_bound {
-            // find if the x is the left point, the right point or between the two ranges
-            let x: u128 = if ranges.len() == 2 {
-                (ranges.first().unwrap().1 + 1) as u128
-            } else if ranges.first().unwrap().0 == 0 {
-                max_bound as u128
-            } else {
-                0
-            };
-            let result: u128 = x * 4000000 + y as u128;
-            return Ok(result.to_string());
+/// scans each row looking for the only cell in the search area
+/// that is not covered by any sensor
+pub fn distress_beacon(puzzle_input: &str, max_bound: i64) -> Result<Option<(i64, i64)>, AocError> {
+    let (sensors, _) = parse_input(puzzle_input.to_string())?;
+
+    Ok((0..=max_bound).find_map(|y| row_gap(&sensors, y, max_bound).map(|x| (x, y))))
+}
+
+/// same as `distress_beacon` but the rows are scanned in parallel,
+/// the scan stops as soon as the row with the gap is found
+pub fn distress_beacon_parallel(
+    puzzle_input: &str,
+    max_bound: i64,
+) -> Result<Option<(i64, i64)>, AocError> {
+    let (sensors, _) = parse_input(puzzle_input.to_string())?;
+
+    Ok((0..=max_bound)
+        .into_par_iter()
+        .find_map_first(|y| row_gap(&sensors, y, max_bound).map(|x| (x, y))))
+}
+
+fn tuning_frequency(beacon: (i64, i64)) -> i64 {
+    beacon.0 * 4_000_000 + beacon.1
+}
+
+fn solve_pt2(puzzle_input: String, max_bound: i64) -> Result<Answer, Box<dyn Error>> {
+    let beacon =
+        distress_beacon_parallel(&puzzle_input, max_bound)?.ok_or("distress beacon not found")?;
+    Ok(Answer::from(tuning_frequency(beacon)))
+}
+
+/// finds the distress beacon checking only the cells just outside
+/// each sensor area, since the beacon is the only uncovered cell it must
+/// lie right beyond the border of some sensor
+fn distress_beacon_perimeter(
+    puzzle_input: &str,
+    max_bound: i64,
+) -> Result<Option<(i64, i64)>, AocError> {
+    let (sensors, _) = parse_input(puzzle_input.to_string())?;
+
+    for sensor in sensors.iter() {
+        let distance = sensor.2 + 1;
+        for dx in 0..=distance {
+            let dy = distance - dx;
+            for (x, y) in [
+                (sensor.0 + dx, sensor.1 + dy),
+                (sensor.0 + dx, sensor.1 - dy),
+                (sensor.0 - dx, sensor.1 + dy),
+                (sensor.0 - dx, sensor.1 - dy),
+            ] {
+                if (x < 0) | (x > max_bound) | (y < 0) | (y > max_bound) {
+                    continue;
+                }
+                if sensors
+                    .iter()
+                    .all(|s| manhattan_distance(&(s.0, s.1), &(x, y)) > s.2)
+                {
+                    return Ok(Some((x, y)));
+                }
+            }
         }
     }
-    Ok("mmm".to_string())
+    Ok(None)
+}
+
+pub fn solve_pt2_perimeter(puzzle_input: String, max_bound: i64) -> Result<Answer, Box<dyn Error>> {
+    let beacon =
+        distress_beacon_perimeter(&puzzle_input, max_bound)?.ok_or("distress beacon not found")?;
+    Ok(Answer::from(tuning_frequency(beacon)))
 }
 
 #[cfg(test)]
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use crate::{error::AocError, Answer};
+
+    use super::{
+        covered_ranges, distress_beacon, distress_beacon_parallel, excluded_counts, merge_ranges,
+        parse_input, solve_pt1, solve_pt2, solve_pt2_perimeter,
+    };
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -206,7 +276,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input, 10)?;
 
-        assert_eq!("26".to_string(), result);
+        assert_eq!(Answer::Int(26), result);
 
         Ok(())
     }
@@ -218,8 +288,167 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input, 20)?;
 
-        assert_eq!("56000011", result);
+        assert_eq!(Answer::Int(56000011), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pt1_same_row_beacons() -> Result<(), Box<dyn Error>> {
+        let puzzle_input = "Sensor at x=0, y=0: closest beacon is at x=2, y=0\n\
+            Sensor at x=10, y=0: closest beacon is at x=12, y=0\n\
+            Sensor at x=5, y=3: closest beacon is at x=2, y=0\n"
+            .to_string();
+        let result = solve_pt1(puzzle_input, 0)?;
+
+        // [-2, 12] is covered and the two beacons are excluded
+        assert_eq!(Answer::Int(13), result);
 
         Ok(())
     }
+
+    #[test]
+    fn test_excluded_counts() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_15_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!(
+            vec![(9, 25), (10, 26), (11, 28)],
+            excluded_counts(&puzzle_input, 9, 11)?
+        );
+        assert_eq!(
+            Answer::from(excluded_counts(&puzzle_input, 10, 10)?[0].1),
+            solve_pt1(puzzle_input.clone(), 10)?
+        );
+        assert!(excluded_counts(&puzzle_input, 11, 9)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pt2_perimeter() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_15_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!(
+            solve_pt2(puzzle_input.clone(), 20)?,
+            solve_pt2_perimeter(puzzle_input, 20)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pt1_large_coordinates() -> Result<(), Box<dyn Error>> {
+        // the distance between sensor and beacon does not fit in an i32
+        let puzzle_input =
+            "Sensor at x=2000000000, y=0: closest beacon is at x=-2000000000, y=0\n".to_string();
+        let result = solve_pt1(puzzle_input, 0)?;
+
+        assert_eq!(Answer::Int(8000000000), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_distress_beacon() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_15_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!(Some((14, 11)), distress_beacon(&puzzle_input, 20)?);
+
+        // a single sensor covers the whole search area
+        let puzzle_input = "Sensor at x=10, y=10: closest beacon is at x=100, y=10\n".to_string();
+
+        assert_eq!(None, distress_beacon(&puzzle_input, 20)?);
+        assert!(solve_pt2(puzzle_input, 20).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_distress_beacon_parallel() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_15_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert_eq!(
+            distress_beacon(&puzzle_input, 20)?,
+            distress_beacon_parallel(&puzzle_input, 20)?
+        );
+
+        // with a larger area many rows have gaps, the first one is still returned
+        assert_eq!(
+            distress_beacon(&puzzle_input, 40)?,
+            distress_beacon_parallel(&puzzle_input, 40)?
+        );
+
+        let puzzle_input = "Sensor at x=10, y=10: closest beacon is at x=100, y=10\n";
+
+        assert_eq!(None, distress_beacon_parallel(puzzle_input, 20)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_covered_ranges() {
+        let sensors = vec![(0, 0, 2), (3, 0, 2)];
+
+        assert_eq!(vec![(-2, 5)], covered_ranges(&sensors, 0));
+        assert_eq!(vec![(-1, 4)], covered_ranges(&sensors, 1));
+        assert_eq!(vec![(0, 0), (3, 3)], covered_ranges(&sensors, 2));
+        assert_eq!(Vec::<(i64, i64)>::new(), covered_ranges(&sensors, 3));
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        // adjacent ranges are coalesced
+        assert_eq!(vec![(0, 5)], merge_ranges(vec![(3, 5), (0, 2)]));
+        // overlapping and contained ranges are coalesced
+        assert_eq!(vec![(-4, 7)], merge_ranges(vec![(0, 7), (-4, 3), (1, 2)]));
+        // disjoint ranges are kept sorted
+        assert_eq!(
+            vec![(-3, -1), (1, 1), (5, 9)],
+            merge_ranges(vec![(5, 9), (1, 1), (-3, -1)])
+        );
+        assert_eq!(Vec::<(i64, i64)>::new(), merge_ranges(Vec::new()));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let sensor = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15\n";
+
+        let error = parse_input(format!("{sensor}Sensor at x=2, y=18\n"))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error,
+            AocError::Parse {
+                day: 15,
+                line: 2,
+                ..
+            }
+        ));
+
+        let error = parse_input(format!(
+            "{sensor}{sensor}Sensor at x=2, y=18: closest beacon is at x=99999999999999999999, y=15\n"
+        ))
+        .err()
+        .unwrap();
+        assert!(matches!(
+            error,
+            AocError::Parse {
+                day: 15,
+                line: 3,
+                ..
+            }
+        ));
+        assert!(error.to_string().contains("x=99999999999999999999"));
+
+        assert!(distress_beacon("Sensor at x=a, y=b\n", 20).is_err());
+        assert!(solve_pt1("Sensor\n".to_string(), 10).is_err());
+    }
 }
@@ -1,43 +1,15 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     error::Error,
-    fs::File,
-    io::Read,
     ops::{Deref, DerefMut},
-    time::Instant,
 };
 
-use log::info;
+use log::warn;
 use regex::Regex;
 
-use crate::ProblemPart;
-
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
-
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_millis();
-            info!("Solved part 1 in {duration} milli seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_millis();
-            info!("Solved part 2 in {duration} milli seconds.");
-            result
-        }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
-}
+use crate::{error::AocError, Answer};
+
+solve_day!(solve_pt1, solve_pt2);
 
 #[derive(Debug, Clone)]
 struct Valve {
@@ -58,41 +30,90 @@ impl DerefMut for Valve {
         &mut self.open
     }
 }
-impl From<&str> for Valve {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for Valve {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        // the tunnels can be singular or plural and lead to one or more valves
         let re = Regex::new(
-            r"(?<NAME>[A-Z]{2}).*?(?<RATE>\d+).*?valves*\s+(?<DESTINATIONS>(?:[A-Z]{2},\s*)*[A-Z]{2})"
-        ).unwrap();
-        let capture = re.captures(value).unwrap();
-        Valve {
-            name: capture
-                .name("NAME")
-                .map(|x| x.as_str().to_string())
-                .unwrap(),
-            flow_rate: capture
-                .name("RATE")
-                .map(|x| x.as_str().parse::<u64>().unwrap())
-                .unwrap(),
-            destinations: capture
-                .name("DESTINATIONS")
-                .map(|x| x.as_str().split(", ").map(|x| x.to_string()).collect())
-                .unwrap(),
+            r"Valve\s+(?<NAME>[A-Z]+)\s+has flow rate=(?<RATE>\d+);\s+tunnels?\s+leads?\s+to\s+valves?\s+(?<DESTINATIONS>[A-Z]+(?:\s*,\s*[A-Z]+)*)",
+        )
+        .unwrap();
+        let capture = re
+            .captures(value)
+            .ok_or_else(|| format!("'{value}' is not a valve description"))?;
+        Ok(Valve {
+            name: capture["NAME"].to_string(),
+            flow_rate: capture["RATE"]
+                .parse::<u64>()
+                .map_err(|error| format!("flow rate={}: {error}", &capture["RATE"]))?,
+            destinations: capture["DESTINATIONS"]
+                .split(',')
+                .map(|x| x.trim().to_string())
+                .collect(),
             open: false,
-        }
+        })
     }
 }
 
-fn parse_input(puzzle_input: String) -> Vec<Valve> {
+fn parse_input(puzzle_input: String) -> Result<Vec<Valve>, AocError> {
     let mut scan: Vec<Valve> = Vec::new();
-    for line in puzzle_input.lines() {
-        let valve = Valve::from(line);
+    for (i, line) in puzzle_input.lines().enumerate() {
+        let valve = Valve::try_from(line).map_err(|msg| AocError::parse(16, i + 1, msg))?;
         scan.push(valve);
     }
-    scan
+
+    // the tunnels must lead to known valves and the search starts from AA
+    let names: HashSet<&String> = scan.iter().map(|v| &v.name).collect();
+    for (i, valve) in scan.iter().enumerate() {
+        if let Some(unknown) = valve.destinations.iter().find(|d| !names.contains(d)) {
+            return Err(AocError::parse(
+                16,
+                i + 1,
+                format!("tunnel leads to the unknown valve {unknown}"),
+            ));
+        }
+    }
+    if !names.contains(&"AA".to_string()) {
+        return Err(AocError::parse(16, scan.len(), "valve AA not found"));
+    }
+
+    let unreachable = unreachable_valves(&scan);
+    if !unreachable.is_empty() {
+        warn!("valves unreachable from AA: {}", unreachable.join(", "));
+    }
+    Ok(scan)
+}
+
+/// returns the names of the valves that cannot be reached from AA,
+/// in the same order of the scan
+fn unreachable_valves(valves: &[Valve]) -> Vec<String> {
+    let by_name: HashMap<&String, &Valve> = valves.iter().map(|v| (&v.name, v)).collect();
+    let mut reached: HashSet<&String> = HashSet::new();
+    let mut to_visit: Vec<&String> = valves
+        .iter()
+        .map(|v| &v.name)
+        .filter(|&n| n == "AA")
+        .collect();
+
+    while let Some(name) = to_visit.pop() {
+        if !reached.insert(name) {
+            continue;
+        }
+        if let Some(valve) = by_name.get(name) {
+            to_visit.extend(valve.destinations.iter().filter(|d| !reached.contains(d)));
+        }
+    }
+
+    valves
+        .iter()
+        .filter(|v| !reached.contains(&v.name))
+        .map(|v| v.name.clone())
+        .collect()
 }
 
 /// from https://en.wikipedia.org/wiki/Floyd%E2%80%93Warshall_algorithm
-fn build_adjacency_matrix(valves: &Vec<Valve>) -> Vec<Vec<u64>> {
+fn build_adjacency_matrix(valves: &[Valve]) -> Vec<Vec<u64>> {
     let mut adjacency: Vec<Vec<u64>> = vec![vec![u64::MAX / 2; valves.len()]; valves.len()];
 
     let mut valve_to_id: HashMap<&String, usize> = HashMap::new();
@@ -125,41 +146,76 @@ fn build_adjacency_matrix(valves: &Vec<Valve>) -> Vec<Vec<u64>> {
     adjacency
 }
 
+/// computes the distances among AA and the valves with flow rate, which are
+/// the only ones the search can target, and returns them with their indices in valves.
+///
+/// AA is always the first valve of the reduced set
+fn reduced_distances(valves: &[Valve]) -> (Vec<Vec<u64>>, Vec<usize>) {
+    let adjacency = build_adjacency_matrix(valves);
+
+    let start_idx = valves.iter().position(|v| v.name == *"AA").unwrap();
+    let mut indices = vec![start_idx];
+    indices.extend(
+        valves
+            .iter()
+            .enumerate()
+            .filter(|(i, v)| (*i != start_idx) & (v.flow_rate > 0))
+            .map(|(i, _)| i),
+    );
+
+    let distances = indices
+        .iter()
+        .map(|&i| indices.iter().map(|&j| adjacency[i][j]).collect())
+        .collect();
+    (distances, indices)
+}
+
 #[derive(Debug, Clone)]
 struct Track {
+    // index in the reduced set of valves, as the bits of the mask
     current_idx: usize,
     track_mask: u64,
     track_flow: u64,
     remaining_time: u64,
+    // indices of the opened valves in order
+    path: Vec<usize>,
 }
 
-fn step(valves: &[Valve], adjacency: &[Vec<u64>], track: &Track) -> Option<Vec<Track>> {
+fn step(
+    valves: &[Valve],
+    distances: &[Vec<u64>],
+    indices: &[usize],
+    track: &Track,
+) -> Option<Vec<Track>> {
     /*
     for the current idx finds all the destinations, compute the time, release
     return all the new tracks as track_mask, track_flow and current_idx
     */
     let mut new_tracks: Vec<Track> = Vec::new();
-    let potential_valves = valves
+    let potential_valves = indices
         .iter()
         .enumerate()
-        .filter(|(i, v)| {
+        .filter(|(i, &v)| {
             // the valve must be closed and with flow rate
-            ((1 << i) & track.track_mask == 0) & (v.flow_rate > 0)
+            ((1 << i) & track.track_mask == 0) & (valves[v].flow_rate > 0)
         })
         .map(|(i, _)| i);
     for destination_id in potential_valves {
         let time = track
             .remaining_time
-            .checked_sub(adjacency[track.current_idx][destination_id])
+            .checked_sub(distances[track.current_idx][destination_id])
             .and_then(|t| t.checked_sub(1))
             .unwrap_or(0);
         if time > 0 {
-            let released_pressure = valves[destination_id].flow_rate * time;
+            let released_pressure = valves[indices[destination_id]].flow_rate * time;
+            let mut path = track.path.clone();
+            path.push(indices[destination_id]);
             new_tracks.push(Track {
                 track_mask: track.track_mask | (1 << destination_id),
                 track_flow: released_pressure + track.track_flow,
                 remaining_time: time,
                 current_idx: destination_id,
+                path,
             })
         }
     }
@@ -170,99 +226,204 @@ fn step(valves: &[Valve], adjacency: &[Vec<u64>], track: &Track) -> Option<Vec<T
     }
 }
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let valves = parse_input(puzzle_input);
-    let adjacency = build_adjacency_matrix(&valves);
+/// returns the best pressure a single agent can release in the given minutes
+pub fn max_pressure(puzzle_input: &str, minutes: u64) -> Result<u64, AocError> {
+    let valves = parse_input(puzzle_input.to_string())?;
+    let (distances, indices) = reduced_distances(&valves);
+
+    let (best_flow, _) = best_path(&valves, &distances, &indices, minutes, true);
+    Ok(best_flow)
+}
+
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    Ok(Answer::from(max_pressure(&puzzle_input, 30)?))
+}
+
+/// returns an upper bound of the pressure the track can still release, as if
+/// each closed valve could be reached and opened straight from the current one
+fn upper_bound(valves: &[Valve], distances: &[Vec<u64>], indices: &[usize], track: &Track) -> u64 {
+    indices
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| (1 << i) & track.track_mask == 0)
+        .map(|(i, &v)| {
+            let time = track
+                .remaining_time
+                .saturating_sub(distances[track.current_idx][i] + 1);
+            valves[v].flow_rate * time
+        })
+        .sum()
+}
 
-    let current_idx = valves.iter().position(|v| v.name == *"AA").unwrap();
-    // 0 means the valve is closed and 1 means that it is open
-    let track_mask: u64 = 0;
+/// explores the tracks a single agent can follow in the given time starting from AA,
+/// skipping the dominated ones, and calls visit on each of them
+///
+/// with prune, the tracks that cannot release more pressure than the best one
+/// found so far are skipped too, hence only the best track is sure to be visited
+fn explore(
+    valves: &[Valve],
+    distances: &[Vec<u64>],
+    indices: &[usize],
+    minutes: u64,
+    prune: bool,
+    mut visit: impl FnMut(&Track),
+) {
     let mut active_tracks: Vec<Track> = vec![Track {
-        current_idx,
+        // AA is the first valve of the reduced set
+        current_idx: 0,
         track_flow: 0,
-        track_mask,
-        remaining_time: 30,
+        track_mask: 0,
+        remaining_time: minutes,
+        path: Vec::new(),
     }];
+    // best flow reached in each position with the same opened valves and remaining time,
+    // a track that does not improve it is dominated and its continuations can be skipped
+    let mut visited: HashMap<(usize, u64, u64), u64> = HashMap::new();
     let mut best_flow = 0;
 
     while let Some(track) = active_tracks.pop() {
-        if let Some(next_tracks) = step(&valves, &adjacency, &track) {
-            for next_track in next_tracks {
-                if next_track.remaining_time > 0 {
-                    active_tracks.push(next_track);
-                } else {
-                    best_flow = best_flow.max(next_track.track_flow);
-                }
+        if prune
+            && (track.track_flow + upper_bound(valves, distances, indices, &track) <= best_flow)
+        {
+            continue;
+        }
+        let key = (track.current_idx, track.track_mask, track.remaining_time);
+        match visited.get(&key) {
+            Some(&flow) if flow >= track.track_flow => continue,
+            _ => {
+                visited.insert(key, track.track_flow);
             }
-        } else {
-            best_flow = best_flow.max(track.track_flow);
+        }
+
+        visit(&track);
+        best_flow = best_flow.max(track.track_flow);
+
+        if let Some(next_tracks) = step(valves, distances, indices, &track) {
+            active_tracks.extend(next_tracks);
         }
     }
+}
 
-    Ok(best_flow.to_string())
+/// explores every track a single agent can follow in the given time
+/// and returns, for each set of opened valves, the best released pressure
+fn best_flow_per_mask(
+    valves: &[Valve],
+    distances: &[Vec<u64>],
+    indices: &[usize],
+    minutes: u64,
+) -> HashMap<u64, u64> {
+    let mut best_flows: HashMap<u64, u64> = HashMap::new();
+    // pruning would skip masks the other agent could complete
+    explore(valves, distances, indices, minutes, false, |track| {
+        // every partial track is a candidate since the other agent can open the remaining valves
+        let best = best_flows.entry(track.track_mask).or_insert(0);
+        *best = (*best).max(track.track_flow);
+    });
+    best_flows
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let valves = parse_input(puzzle_input);
-    let adjacency = build_adjacency_matrix(&valves);
+/// returns the best released pressure for a single agent in the given time
+/// together with the indices of the valves to open, in order
+fn best_path(
+    valves: &[Valve],
+    distances: &[Vec<u64>],
+    indices: &[usize],
+    minutes: u64,
+    prune: bool,
+) -> (u64, Vec<usize>) {
+    let mut best_flow = 0;
+    let mut best_path: Vec<usize> = Vec::new();
+    explore(valves, distances, indices, minutes, prune, |track| {
+        if track.track_flow > best_flow {
+            best_flow = track.track_flow;
+            best_path = track.path.clone();
+        }
+    });
+    (best_flow, best_path)
+}
 
-    let current_idx = valves.iter().position(|v| v.name == *"AA").unwrap();
-    // 0 means the valve is closed and 1 means that it is open
-    let track_mask: u64 = 0;
-    let mut active_tracks: Vec<Track> = vec![Track {
-        current_idx,
-        track_flow: 0,
-        track_mask,
-        remaining_time: 26,
-    }];
-    let mut closed_tracks: Vec<Track> = Vec::new();
+/// returns the best released pressure for a single agent in the given time
+/// together with the names of the valves to open, in order
+pub fn best_plan(puzzle_input: &str, minutes: u64) -> Result<(u64, Vec<String>), AocError> {
+    let valves = parse_input(puzzle_input.to_string())?;
+    let (distances, indices) = reduced_distances(&valves);
 
-    while let Some(track) = active_tracks.pop() {
-        if let Some(next_tracks) = step(&valves, &adjacency, &track) {
-            for next_track in next_tracks {
-                if next_track.remaining_time > 0 {
-                    // we put it because we need to chceck all the partial tracks
-                    closed_tracks.push(next_track.clone());
-                    active_tracks.push(next_track);
-                } else {
-                    closed_tracks.push(next_track);
-                }
-            }
-        } else {
-            closed_tracks.push(track);
-        }
+    let (best_flow, best_path) = best_path(&valves, &distances, &indices, minutes, true);
+    let plan = best_path
+        .into_iter()
+        .map(|i| valves[i].name.clone())
+        .collect();
+    Ok((best_flow, plan))
+}
+
+/// returns, for each valve opened by the best single agent plan, the pressure
+/// it releases, that is its flow rate times the minutes it stays open
+pub fn contribution_breakdown(
+    puzzle_input: &str,
+    minutes: u64,
+) -> Result<Vec<(String, u64)>, AocError> {
+    let valves = parse_input(puzzle_input.to_string())?;
+    let (distances, indices) = reduced_distances(&valves);
+
+    let (_, best_path) = best_path(&valves, &distances, &indices, minutes, true);
+    // we follow the plan from AA, which is the first valve of the reduced set
+    let mut current_idx = 0;
+    let mut remaining_time = minutes;
+    let mut contributions = Vec::new();
+    for valve_idx in best_path {
+        let next_idx = indices.iter().position(|&i| i == valve_idx).unwrap();
+        // the plan only contains valves opened in time
+        remaining_time -= distances[current_idx][next_idx] + 1;
+        current_idx = next_idx;
+
+        let valve = &valves[valve_idx];
+        contributions.push((valve.name.clone(), valve.flow_rate * remaining_time));
     }
+    Ok(contributions)
+}
+
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let valves = parse_input(puzzle_input)?;
+    let (distances, indices) = reduced_distances(&valves);
+    let best_flows = best_flow_per_mask(&valves, &distances, &indices, 26);
 
-    // Now we find the complementar tracks with highest sum
+    // we find the pair of disjoint sets of valves with the highest sum,
+    // one opened by us and the other by the elephant
     let mut best_flow = 0;
-    for track in closed_tracks.iter() {
-        for other in closed_tracks.iter() {
-            if track.track_mask & other.track_mask == 0 {
-                best_flow = best_flow.max(track.track_flow + other.track_flow);
+    for (mask, flow) in best_flows.iter() {
+        for (other_mask, other_flow) in best_flows.iter() {
+            if mask & other_mask == 0 {
+                best_flow = best_flow.max(flow + other_flow);
             }
         }
     }
 
-    Ok(best_flow.to_string())
+    Ok(Answer::from(best_flow))
 }
 
 #[cfg(test)]
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use crate::{error::AocError, Answer};
+
+    use super::{
+        best_flow_per_mask, best_path, best_plan, contribution_breakdown, max_pressure,
+        parse_input, reduced_distances, solve_pt1, solve_pt2, unreachable_valves, Valve,
+    };
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
-        env_logger::Builder::new()
+        // the logger is global, so it is set only if no other test did it
+        let _ = env_logger::Builder::new()
             .filter_level(log::LevelFilter::Debug)
-            .init();
+            .try_init();
         let mut file = File::open("inputs/day_16_example.txt")?;
         let mut puzzle_input = String::new();
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!("1651".to_string(), result);
+        assert_eq!(Answer::Int(1651), result);
 
         Ok(())
     }
@@ -274,8 +435,200 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!("1707".to_string(), result);
+        assert_eq!(Answer::Int(1707), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_flow_per_mask() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_16_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let valves = parse_input(puzzle_input)?;
+        let (distances, indices) = reduced_distances(&valves);
+        let best_flows = best_flow_per_mask(&valves, &distances, &indices, 30);
+
+        // the optimal plan opens BB, CC, DD, EE, HH and JJ
+        let mask = (1 << 1) | (1 << 2) | (1 << 3) | (1 << 4) | (1 << 5) | (1 << 6);
+        assert_eq!(Some(&1651), best_flows.get(&mask));
+        assert_eq!(Some(&1651), best_flows.values().max());
+        // opening only DD on the first step
+        assert_eq!(Some(&(20 * 28)), best_flows.get(&(1 << 3)));
+        assert_eq!(Some(&0), best_flows.get(&0));
 
         Ok(())
     }
+
+    #[test]
+    fn test_best_plan() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_16_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let (flow, plan) = best_plan(&puzzle_input, 30)?;
+
+        assert_eq!(Answer::from(flow), solve_pt1(puzzle_input)?);
+        assert_eq!(vec!["DD", "BB", "JJ", "HH", "EE", "CC"], plan);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_contribution_breakdown() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_16_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let contributions = contribution_breakdown(&puzzle_input, 30)?;
+
+        assert_eq!(
+            vec![
+                ("DD".to_string(), 20 * 28),
+                ("BB".to_string(), 13 * 25),
+                ("JJ".to_string(), 21 * 21),
+                ("HH".to_string(), 22 * 13),
+                ("EE".to_string(), 3 * 9),
+                ("CC".to_string(), 2 * 6),
+            ],
+            contributions
+        );
+        assert_eq!(
+            Answer::from(contributions.iter().map(|(_, c)| c).sum::<u64>()),
+            solve_pt1(puzzle_input)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pruned_search() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_16_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let valves = parse_input(puzzle_input)?;
+        let (distances, indices) = reduced_distances(&valves);
+
+        for minutes in 0..=30 {
+            let (pruned, _) = best_path(&valves, &distances, &indices, minutes, true);
+            let (unpruned, _) = best_path(&valves, &distances, &indices, minutes, false);
+
+            assert_eq!(unpruned, pruned, "{minutes} minutes");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_valve() -> Result<(), Box<dyn Error>> {
+        let valve = Valve::try_from("Valve HH has flow rate=22; tunnel leads to valve GG")?;
+        assert_eq!("HH", valve.name);
+        assert_eq!(22, valve.flow_rate);
+        assert_eq!(vec!["GG"], valve.destinations);
+
+        let valve = Valve::try_from("Valve A has flow rate=0; tunnels lead to valves B")?;
+        assert_eq!("A", valve.name);
+        assert_eq!(vec!["B"], valve.destinations);
+
+        let valve = Valve::try_from("Valve AAA has flow rate=3; tunnels lead to valves DD,II, B")?;
+        assert_eq!("AAA", valve.name);
+        assert_eq!(3, valve.flow_rate);
+        assert_eq!(vec!["DD", "II", "B"], valve.destinations);
+
+        assert!(Valve::try_from("Valve HH has flow rate=x; tunnel leads to valve GG").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reduced_distances() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_16_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let valves = parse_input(puzzle_input)?;
+        let (distances, indices) = reduced_distances(&valves);
+
+        // AA followed by BB, CC, DD, EE, HH and JJ
+        assert_eq!(vec![0, 1, 2, 3, 4, 7, 9], indices);
+        assert_eq!(vec![0, 1, 2, 1, 2, 5, 2], distances[0]);
+        // from HH to JJ we go through GG, FF, EE, DD, AA and II
+        assert_eq!(7, distances[5][6]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_pressure() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_16_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        // DD, BB and then JJ with one minute left
+        assert_eq!(20 * 8 + 13 * 5 + 21, max_pressure(&puzzle_input, 10)?);
+        assert_eq!(0, max_pressure(&puzzle_input, 1)?);
+        assert_eq!(1651, max_pressure(&puzzle_input, 30)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unreachable_valves() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_16_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+
+        assert!(unreachable_valves(&parse_input(puzzle_input)?).is_empty());
+
+        // CC and DD are only connected to each other, and EE only leads to AA
+        let puzzle_input = "Valve AA has flow rate=0; tunnels lead to valves BB\n\
+            Valve BB has flow rate=5; tunnels lead to valves AA\n\
+            Valve CC has flow rate=50; tunnels lead to valves DD\n\
+            Valve DD has flow rate=0; tunnels lead to valves CC\n\
+            Valve EE has flow rate=7; tunnels lead to valves AA\n";
+        let valves = parse_input(puzzle_input.to_string())?;
+
+        assert_eq!(vec!["CC", "DD", "EE"], unreachable_valves(&valves));
+        // the unreachable valves are never opened
+        assert_eq!(5 * 28, max_pressure(puzzle_input, 30)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let error = parse_input(
+            "Valve AA has flow rate=0; tunnels lead to valves BB\nValve BB has flow rate=; tunnels lead to valves AA\n"
+                .to_string(),
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(
+            error,
+            AocError::Parse {
+                day: 16,
+                line: 2,
+                ..
+            }
+        ));
+
+        let error = parse_input(
+            "Valve AA has flow rate=0; tunnels lead to valves BB\nValve BB has flow rate=3; tunnels lead to valves CC\n"
+                .to_string(),
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(
+            error,
+            AocError::Parse {
+                day: 16,
+                line: 2,
+                ..
+            }
+        ));
+        assert!(error.to_string().contains("unknown valve CC"));
+
+        let error = parse_input("Valve BB has flow rate=3; tunnel leads to valve BB\n".to_string())
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("valve AA not found"));
+
+        assert!(max_pressure("Valve AA\n", 30).is_err());
+    }
 }
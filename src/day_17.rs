@@ -1,50 +1,45 @@
-use std::{error::Error, fs::File, io::Read, time::Instant, vec};
+use std::{collections::HashMap, error::Error};
 
-use log::info;
+use log::debug;
 
-use crate::ProblemPart;
+use crate::Answer;
 
-pub fn solve(puzzle_input: &str, part: ProblemPart) -> Result<(), Box<dyn Error>> {
-    let mut file = File::open(puzzle_input)?;
-    let mut puzzle_input = String::new();
-    file.read_to_string(&mut puzzle_input)?;
+solve_day!(solve_pt1, solve_pt2);
 
-    let result = match part {
-        ProblemPart::One => {
-            info!("Start solving part 1");
-            let start = Instant::now();
-            let result = solve_pt1(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 1 in {duration} seconds.");
-            result
-        }
-        ProblemPart::Two => {
-            info!("Start solving part 2");
-            let start = Instant::now();
-            let result = solve_pt2(puzzle_input)?;
-            let duration = start.elapsed().as_secs();
-            info!("Solved part 2 in {duration} seconds.");
-            result
-        }
-    };
-    info!("Problem solution is {}", result);
-    Ok(())
+/// parses the jets mapping the left character to -1 and the right one to 1,
+/// whitespaces are skipped and any other character is an error
+pub fn parse_jets(input: &str, left: char, right: char) -> Result<Vec<i8>, Box<dyn Error>> {
+    if left == right {
+        return Err(format!("the jets must use two different characters, got '{left}'").into());
+    }
+    let jets = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            c if c == left => Ok(-1),
+            c if c == right => Ok(1),
+            c => Err(format!(
+                "'{c}' is not a jet, expected '{left}' or '{right}'"
+            )),
+        })
+        .collect::<Result<Vec<i8>, String>>()?;
+    if jets.is_empty() {
+        return Err("no jets".into());
+    }
+    Ok(jets)
 }
 
-fn parse_input(puzzle_input: String) -> Vec<i8> {
-    puzzle_input
-        .chars()
-        .map(|c| if c == '<' { -1 } else { 1 })
-        .collect()
+fn parse_input(puzzle_input: String) -> Result<Vec<i8>, Box<dyn Error>> {
+    parse_jets(&puzzle_input, '<', '>')
 }
 
 /// A Rock is composed of the area that is a vector of
-/// u8 representing the occupied bits and the coordinates of the first
+/// u16 representing the occupied bits and the coordinates of the first
 /// element of the bottom line
+#[derive(Clone)]
 struct Rock {
-    area: Vec<u8>,
+    area: Vec<u16>,
     heigth: u32,
-    rock_type: RockType,
 }
 
 #[derive(PartialEq)]
@@ -56,369 +51,457 @@ enum RockType {
     Square,
 }
 
-fn rock_factory(chamber_width: u8, rock_type: &RockType) -> Rock {
-    // shift bits by chamber_width - falling_rock.width - falling_rock.coordinates.0
-    match rock_type {
-        RockType::Minus => Rock {
-            area: vec![15 << (chamber_width - 4 - 2)],
-            heigth: 0,
-            rock_type: RockType::Minus,
-        },
-        RockType::Plus => Rock {
-            area: vec![
-                2 << (chamber_width - 3 - 2),
-                7 << (chamber_width - 3 - 2),
-                2 << (chamber_width - 3 - 2),
-            ],
-            heigth: 0,
-            rock_type: RockType::Plus,
-        },
-        RockType::ReverseL => Rock {
-            area: vec![
-                7 << (chamber_width - 3 - 2),
-                1 << (chamber_width - 3 - 2),
-                1 << (chamber_width - 3 - 2),
-            ],
-            heigth: 0,
-            rock_type: RockType::ReverseL,
-        },
-        RockType::Pipe => Rock {
-            area: vec![
-                1 << (chamber_width - 1 - 2),
-                1 << (chamber_width - 1 - 2),
-                1 << (chamber_width - 1 - 2),
-                1 << (chamber_width - 1 - 2),
-            ],
-            heigth: 0,
-            rock_type: RockType::Pipe,
-        },
-        RockType::Square => Rock {
-            area: vec![3 << (chamber_width - 2 - 2), 3 << (chamber_width - 2 - 2)],
-            heigth: 0,
-            rock_type: RockType::Square,
-        },
-    }
+/// The shape of a rock as a bitmap with one u16 for each line starting from
+/// the bottom one, the leftmost cell is the highest of the width bits
+#[derive(Debug, Clone)]
+pub struct RockShape {
+    pub area: Vec<u16>,
+    pub width: u8,
 }
 
-fn solve_pt1(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let jet_sequence = parse_input(puzzle_input);
-    let mut jet_pattern = jet_sequence.iter().cycle();
-    let chamber_width: u8 = 7;
+impl From<&RockType> for RockShape {
+    fn from(rock_type: &RockType) -> Self {
+        match rock_type {
+            RockType::Minus => RockShape {
+                area: vec![15],
+                width: 4,
+            },
+            RockType::Plus => RockShape {
+                area: vec![2, 7, 2],
+                width: 3,
+            },
+            RockType::ReverseL => RockShape {
+                area: vec![7, 1, 1],
+                width: 3,
+            },
+            RockType::Pipe => RockShape {
+                area: vec![1, 1, 1, 1],
+                width: 1,
+            },
+            RockType::Square => RockShape {
+                area: vec![3, 3],
+                width: 2,
+            },
+        }
+    }
+}
 
-    let rocks = vec![
+/// returns the shapes of the five rocks of the puzzle in the order they fall
+pub fn default_shapes() -> Vec<RockShape> {
+    [
         RockType::Minus,
         RockType::Plus,
         RockType::ReverseL,
         RockType::Pipe,
         RockType::Square,
-    ];
-    let mut rock_cycle = rocks.iter().cycle();
-    // the chamber is a vector of bitmask with 8 bits representing the chamber width
-    // 0 element is bottom and higher elements represent the heght
-    let mut chamber: Vec<u8> = Vec::new();
-    // add floor which is represented as 1111111
-    chamber.push((1 << chamber_width) - 1);
+    ]
+    .iter()
+    .map(RockShape::from)
+    .collect()
+}
 
-    for _ in 0..2022 {
-        let mut falling_rock = rock_factory(chamber_width, rock_cycle.next().unwrap());
-        // the rock starts 3 units above the highest rock in the room
-        falling_rock.heigth = chamber.len() as u32 + 3;
-        loop {
-            // get the jet and move the rock
-            let &jet = jet_pattern.next().unwrap();
-            if jet > 0 {
-                let mut can_move = true;
-                for (i, falling_line) in falling_rock.area.iter().enumerate() {
-                    let chamber_line_id = falling_rock.heigth + i as u32;
-                    if let Some(chamber_line) = chamber.get(chamber_line_id as usize) {
-                        // check if the rock can move or it hits other rocks or the chamber boundary
-                        if (chamber_line & (falling_line >> 1) != 0) | (falling_line & 1 != 0) {
-                            can_move = false;
-                            break;
-                        }
-                    } else {
-                        // check only if the rock hits the chamber boundary
-                        if falling_line & 1 != 0 {
-                            can_move = false;
-                            break;
-                        }
+fn rock_factory(chamber_width: u8, shape: &RockShape) -> Result<Rock, Box<dyn Error>> {
+    if shape.width + 2 > chamber_width {
+        return Err(format!(
+            "a rock of width {} does not fit in a chamber of width {chamber_width}",
+            shape.width
+        )
+        .into());
+    }
+    if shape.area.iter().all(|&line| line == 0) {
+        return Err("the rock must occupy at least one cell".into());
+    }
+    if shape.area.iter().any(|line| line >> shape.width != 0) {
+        return Err(format!("the rock lines exceed the rock width {}", shape.width).into());
+    }
+    // the left edge is two units away from the left wall, hence
+    // we shift bits by chamber_width - rock width - 2
+    Ok(Rock {
+        area: shape
+            .area
+            .iter()
+            .map(|line| line << (chamber_width - shape.width - 2))
+            .collect(),
+        heigth: 0,
+    })
+}
+
+/// moves the falling rock with the jets starting from jet_id until it comes to rest
+/// and adds it to the chamber, returns the id of the next jet
+fn drop_rock(
+    chamber: &mut Vec<u16>,
+    falling_rock: &mut Rock,
+    jets: &[i8],
+    jet_id: usize,
+    chamber_width: u8,
+) -> usize {
+    let mut jet_id = jet_id;
+    // the rock starts 3 units above the highest rock in the room
+    falling_rock.heigth = chamber.len() as u32 + 3;
+    loop {
+        // get the jet and move the rock
+        let jet = jets[jet_id];
+        jet_id = (jet_id + 1) % jets.len();
+        if jet > 0 {
+            let mut can_move = true;
+            for (i, falling_line) in falling_rock.area.iter().enumerate() {
+                let chamber_line_id = falling_rock.heigth + i as u32;
+                if let Some(chamber_line) = chamber.get(chamber_line_id as usize) {
+                    // check if the rock can move or it hits other rocks or the chamber boundary
+                    if (chamber_line & (falling_line >> 1) != 0) | (falling_line & 1 != 0) {
+                        can_move = false;
+                        break;
                     }
-                }
-                if can_move {
-                    for falling_line in falling_rock.area.iter_mut() {
-                        *falling_line >>= 1;
+                } else {
+                    // check only if the rock hits the chamber boundary
+                    if falling_line & 1 != 0 {
+                        can_move = false;
+                        break;
                     }
                 }
-            } else {
-                let mut can_move = true;
-                for (i, falling_line) in falling_rock.area.iter().enumerate() {
-                    let chamber_line_id = falling_rock.heigth + i as u32;
-                    if let Some(chamber_line) = chamber.get(chamber_line_id as usize) {
-                        // check if the rock can move or if it hits other rocks or the chamber boundary
-                        if (chamber_line & (falling_line << 1) != 0)
-                            | ((falling_line << 1) & (1 << chamber_width) != 0)
-                        {
-                            can_move = false;
-                            break;
-                        }
-                    } else {
-                        // check only if the rock hits the chamber boundary
-                        if (falling_line << 1) & (1 << chamber_width) != 0 {
-                            can_move = false;
-                            break;
-                        }
-                    }
+            }
+            if can_move {
+                for falling_line in falling_rock.area.iter_mut() {
+                    *falling_line >>= 1;
                 }
-                if can_move {
-                    for falling_line in falling_rock.area.iter_mut() {
-                        *falling_line <<= 1;
+            }
+        } else {
+            let mut can_move = true;
+            for (i, falling_line) in falling_rock.area.iter().enumerate() {
+                let chamber_line_id = falling_rock.heigth + i as u32;
+                if let Some(chamber_line) = chamber.get(chamber_line_id as usize) {
+                    // check if the rock can move or if it hits other rocks or the chamber boundary
+                    if (chamber_line & (falling_line << 1) != 0)
+                        | ((falling_line << 1) & (1 << chamber_width) != 0)
+                    {
+                        can_move = false;
+                        break;
+                    }
+                } else {
+                    // check only if the rock hits the chamber boundary
+                    if (falling_line << 1) & (1 << chamber_width) != 0 {
+                        can_move = false;
+                        break;
                     }
                 }
             }
-            // the rock can go down if the chamber height is lower than the y coordinate
-            // of the rock
-            if (chamber.len() as u32) < falling_rock.heigth {
-                falling_rock.heigth -= 1;
-            } else {
-                // here we check if there is a rock under the following one otherwise
-                // we can go down again
-
-                /*
-                for each line of the rock we check if the chamber overlaps with the line
-                as it would one step down
-                */
-                let mut overlapped = false;
-                for (i, falling_line) in falling_rock.area.iter().enumerate() {
-                    let chamber_line_id = falling_rock.heigth - 1 + i as u32;
-                    if let Some(chamber_line) = chamber.get(chamber_line_id as usize) {
-                        if chamber_line & falling_line != 0 {
-                            // they are overlapped, hence we cannot go down
-                            overlapped = true;
-                            break;
-                        }
+            if can_move {
+                for falling_line in falling_rock.area.iter_mut() {
+                    *falling_line <<= 1;
+                }
+            }
+        }
+        // the rock can go down if the chamber height is lower than the y coordinate
+        // of the rock
+        if (chamber.len() as u32) < falling_rock.heigth {
+            falling_rock.heigth -= 1;
+        } else {
+            // here we check if there is a rock under the following one otherwise
+            // we can go down again
+
+            /*
+            for each line of the rock we check if the chamber overlaps with the line
+            as it would one step down
+            */
+            let mut overlapped = false;
+            for (i, falling_line) in falling_rock.area.iter().enumerate() {
+                let chamber_line_id = falling_rock.heigth - 1 + i as u32;
+                if let Some(chamber_line) = chamber.get(chamber_line_id as usize) {
+                    if chamber_line & falling_line != 0 {
+                        // they are overlapped, hence we cannot go down
+                        overlapped = true;
+                        break;
                     }
                 }
-                if overlapped {
-                    // the rock cannot go down anymore so we proceed with the loop
-                    for (i, falling_line) in falling_rock.area.iter().enumerate() {
-                        let chamber_line_id = falling_rock.heigth + i as u32;
-                        if let Some(chamber_line) = chamber.get_mut(chamber_line_id as usize) {
-                            *chamber_line |= falling_line;
-                        } else {
-                            chamber.push(*falling_line);
-                        }
+            }
+            if overlapped {
+                // the rock cannot go down anymore so we add it to the chamber
+                for (i, falling_line) in falling_rock.area.iter().enumerate() {
+                    let chamber_line_id = falling_rock.heigth + i as u32;
+                    if let Some(chamber_line) = chamber.get_mut(chamber_line_id as usize) {
+                        *chamber_line |= falling_line;
+                    } else {
+                        chamber.push(*falling_line);
                     }
-                    break;
-                } else {
-                    falling_rock.heigth -= 1;
                 }
+                return jet_id;
+            } else {
+                falling_rock.heigth -= 1;
             }
         }
     }
-
-    Ok((chamber.len() - 1).to_string())
 }
 
-fn solve_pt2(puzzle_input: String) -> Result<String, Box<dyn Error>> {
-    let jet_sequence = parse_input(puzzle_input);
-    let mut jet_pattern = jet_sequence.iter().enumerate().cycle();
-    let chamber_width: u8 = 7;
+/// draws the rocks settled in the chamber with the top of the tower first,
+/// the floor is not drawn
+pub fn render_chamber(chamber: &[u16], width: u8) -> String {
+    let mut result = String::new();
+    for line in chamber.iter().skip(1).rev() {
+        for c in (0..width).rev() {
+            result.push(if line & (1 << c) != 0 { '#' } else { '.' });
+        }
+        result.push('\n');
+    }
+    result
+}
 
-    let rocks = vec![
-        RockType::Minus,
-        RockType::Plus,
-        RockType::ReverseL,
-        RockType::Pipe,
-        RockType::Square,
-    ];
-    let mut rock_cycle = rocks.iter().cycle();
-    // the chamber is a vector of bitmask with 8 bits representing the chamber width
-    // 0 element is bottom and higher elements represent the heght
-    let mut chamber: Vec<u8> = Vec::new();
-    // add floor which is represented as 1111111
-    chamber.push((1 << chamber_width) - 1);
+/// returns, for each column from the left one, the height of its topmost
+/// filled cell, the floor is at height 0
+pub fn surface_profile(chamber: &[u16], width: u8) -> Vec<u32> {
+    (0..width)
+        .rev()
+        .map(|c| {
+            chamber
+                .iter()
+                .rposition(|line| line & (1 << c) != 0)
+                .unwrap_or(0) as u32
+        })
+        .collect()
+}
 
-    // Encode the state of the felt rocks and check if it repeats
-    // then multiply this height for the remaining iterations
-    // the state is the or between K lines of the chamber
-    let buffer_size = 10;
-    // the state is composed of an encoding ot the rocks in the chamber, the rock that has fallen and the jet id
-    let mut chamber_state_history: Vec<(i128, (u128, RockType, usize))> = vec![];
-
-    let max_iterations = 1000000000000_i128;
-    let mut iteration_heights: Vec<usize> = Vec::new();
-    // this variable is set when a cycle in the falling rocks is found
-    let mut state_match_iteration: i128 = 0;
-
-    'rocks_iter: for iteration in 0..max_iterations {
-        let mut falling_rock = rock_factory(chamber_width, rock_cycle.next().unwrap());
-        // the rock starts 3 units above the highest rock in the room
-        falling_rock.heigth = chamber.len() as u32 + 3;
-        'falling_loop: loop {
-            // get the jet and move the rock
-            let (jet_id, &jet) = jet_pattern.next().unwrap();
-            if jet > 0 {
-                let mut can_move = true;
-                for (i, falling_line) in falling_rock.area.iter().enumerate() {
-                    let chamber_line_id = falling_rock.heigth + i as u32;
-                    if let Some(chamber_line) = chamber.get(chamber_line_id as usize) {
-                        // check if the rock can move or it hits other rocks or the chamber boundary
-                        if (chamber_line & (falling_line >> 1) != 0) | (falling_line & 1 != 0) {
-                            can_move = false;
-                            break;
-                        }
-                    } else {
-                        // check only if the rock hits the chamber boundary
-                        if falling_line & 1 != 0 {
-                            can_move = false;
-                            break;
-                        }
-                    }
-                }
-                if can_move {
-                    for falling_line in falling_rock.area.iter_mut() {
-                        *falling_line >>= 1;
-                    }
-                }
-            } else {
-                let mut can_move = true;
-                for (i, falling_line) in falling_rock.area.iter().enumerate() {
-                    let chamber_line_id = falling_rock.heigth + i as u32;
-                    if let Some(chamber_line) = chamber.get(chamber_line_id as usize) {
-                        // check if the rock can move or if it hits other rocks or the chamber boundary
-                        if (chamber_line & (falling_line << 1) != 0)
-                            | ((falling_line << 1) & (1 << chamber_width) != 0)
-                        {
-                            can_move = false;
-                            break;
-                        }
-                    } else {
-                        // check only if the rock hits the chamber boundary
-                        if (falling_line << 1) & (1 << chamber_width) != 0 {
-                            can_move = false;
-                            break;
-                        }
-                    }
-                }
-                if can_move {
-                    for falling_line in falling_rock.area.iter_mut() {
-                        *falling_line <<= 1;
-                    }
-                }
+/// width of the chamber of the puzzle
+const DEFAULT_CHAMBER_WIDTH: u8 = 7;
+/// the chamber lines are u16 and the bit on the left of the chamber is used to check the wall
+const MAX_CHAMBER_WIDTH: u8 = 15;
+
+/// gaps deeper than this number of lines below the top are not part of the surface,
+/// otherwise a column that stays open down to the floor would never repeat its state
+const MAX_SURFACE_DEPTH: usize = 128;
+
+/// returns the empty cells a falling rock can reach from the top of the chamber,
+/// as one bitmask for each line starting from the top one.
+///
+/// The rocks only move down, left and right hence the cells are flooded from the
+/// line above the tower, the other ones will never change and do not matter anymore
+fn reachable_surface(chamber: &[u16], chamber_width: u8) -> Vec<u16> {
+    let full_line: u16 = (1 << chamber_width) - 1;
+    let mut surface: Vec<u16> = Vec::new();
+    // the line above the tower is empty
+    let mut reachable = full_line;
+    for &chamber_line in chamber.iter().rev().take(MAX_SURFACE_DEPTH) {
+        // we go down from the reachable cells and then left and right as far as possible
+        let mut line_reachable = reachable & !chamber_line;
+        loop {
+            let expanded = (line_reachable | (line_reachable << 1) | (line_reachable >> 1))
+                & !chamber_line
+                & full_line;
+            if expanded == line_reachable {
+                break;
             }
-            // the rock can go down if the chamber height is lower than the y coordinate
-            // of the rock
-            if (chamber.len() as u32) < falling_rock.heigth {
-                falling_rock.heigth -= 1;
-            } else {
-                // here we check if there is a rock under the following one otherwise
-                // we can go down again
-
-                /*
-                for each line of the rock we check if the chamber overlaps with the line
-                as it would one step down
-                */
-                let mut overlapped = false;
-                for (i, falling_line) in falling_rock.area.iter().enumerate() {
-                    let chamber_line_id = falling_rock.heigth - 1 + i as u32;
-                    if let Some(chamber_line) = chamber.get(chamber_line_id as usize) {
-                        if chamber_line & falling_line != 0 {
-                            // they are overlapped, hence we cannot go down
-                            overlapped = true;
-                            break;
-                        }
-                    }
-                }
-                if overlapped {
-                    // the rock cannot go down anymore so we proceed with the loop
-                    for (i, falling_line) in falling_rock.area.iter().enumerate() {
-                        let chamber_line_id = falling_rock.heigth + i as u32;
-                        if let Some(chamber_line) = chamber.get_mut(chamber_line_id as usize) {
-                            *chamber_line |= falling_line;
-                        } else {
-                            chamber.push(*falling_line);
-                        }
-                    }
-                    // build the chamber state
-                    if chamber.len() > buffer_size {
-                        let mut chamber_state: u128 = 0;
-                        let mut covered_bits: u8 = 0;
-                        for i in 0..buffer_size {
-                            let mut chamber_line = *chamber.get(chamber.len() - 1 - i).unwrap();
-                            chamber_line ^= covered_bits;
-                            covered_bits |= chamber_line;
-                            chamber_state |= (chamber_line as u128) << (8 * i);
-                        }
-                        let state_match = chamber_state_history
-                            .iter()
-                            .filter(|&x| {
-                                (x.1 .0 == chamber_state)
-                                    & (x.1 .1 == falling_rock.rock_type)
-                                    & (x.1 .2 == jet_id)
-                            })
-                            .collect::<Vec<&(i128, (u128, RockType, usize))>>();
-                        if let Some(state_match_value) = state_match.first() {
-                            state_match_iteration = state_match_value.0;
-                        }
-                        if !state_match.is_empty() {
-                            chamber_state_history
-                                .push((iteration, (chamber_state, falling_rock.rock_type, jet_id)));
-                            iteration_heights.push(chamber.len() - 1);
-                            break 'rocks_iter;
-                        } else {
-                            chamber_state_history
-                                .push((iteration, (chamber_state, falling_rock.rock_type, jet_id)));
-                        }
-                    }
+            line_reachable = expanded;
+        }
+        if line_reachable == 0 {
+            break;
+        }
+        surface.push(line_reachable);
+        reachable = line_reachable;
+    }
+    surface
+}
 
-                    break 'falling_loop;
-                } else {
-                    falling_rock.heigth -= 1;
-                }
+/// heights of the tower until the state of the chamber repeats, from then on
+/// every cycle_length rocks the tower grows of the same height
+struct TowerCycle {
+    // heights[k] is the height of the tower after k + 1 rocks
+    heights: Vec<u64>,
+    // index of the rock after which the state is reached for the first time
+    cycle_start: u64,
+    cycle_length: u64,
+    // the longest the chamber has been while looking for the cycle
+    max_chamber_length: usize,
+}
+
+impl TowerCycle {
+    /// returns the height of the tower after count rocks have fallen
+    fn height(&self, count: u64) -> u64 {
+        if count <= self.heights.len() as u64 {
+            return count
+                .checked_sub(1)
+                .map(|k| self.heights[k as usize])
+                .unwrap_or(0);
+        }
+        let cycle_end = self.cycle_start + self.cycle_length;
+        let cycle_height =
+            self.heights[cycle_end as usize] - self.heights[self.cycle_start as usize];
+
+        // the last rock has index count - 1, we count how many complete cycles
+        // are between it and the cycle start and where it falls in the last one
+        let remaining_rocks = count - 1 - self.cycle_start;
+        let complete_repetitions = remaining_rocks / self.cycle_length;
+        let rocks_after_cycles = remaining_rocks % self.cycle_length;
+
+        // height reached by the rock in the same position of the first cycle,
+        // it contains the height before the cycle and the partial cycle
+        let partial_cycle_height = self.heights[(self.cycle_start + rocks_after_cycles) as usize];
+        complete_repetitions * cycle_height + partial_cycle_height
+    }
+
+    /// returns the minimum number of rocks after which the tower
+    /// is at least target_height high
+    fn rocks_for(&self, target_height: u64) -> u64 {
+        if target_height == 0 {
+            return 0;
+        }
+        // the height never decreases, so we double the count until the target
+        // is reached and then we bisect between the last two counts
+        let mut low = 0;
+        let mut high = 1;
+        while self.height(high) < target_height {
+            low = high;
+            high *= 2;
+        }
+        // here height(low) < target_height <= height(high)
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if self.height(middle) < target_height {
+                low = middle;
+            } else {
+                high = middle;
             }
         }
-        iteration_heights.push(chamber.len() - 1);
+        high
     }
+}
 
-    let &repeated_state = chamber_state_history
+/// drops the rocks, cycling through the given shapes, until the state of the chamber repeats.
+///
+/// The state of the chamber is encoded after each rock and, since the number
+/// of states is finite, sooner or later one of them is reached again
+fn find_cycle(
+    jets: &[i8],
+    shapes: &[RockShape],
+    chamber_width: u8,
+) -> Result<TowerCycle, Box<dyn Error>> {
+    if chamber_width > MAX_CHAMBER_WIDTH {
+        return Err(format!("the chamber width must be at most {MAX_CHAMBER_WIDTH}").into());
+    }
+    if shapes.is_empty() {
+        return Err("at least one rock shape is needed".into());
+    }
+    if jets.is_empty() {
+        return Err("no jets".into());
+    }
+    let rocks = shapes
         .iter()
-        .filter(|x| x.0 == state_match_iteration)
-        .collect::<Vec<&(i128, (u128, RockType, usize))>>()
-        .first()
-        .unwrap();
-    let cycle_length = chamber_state_history.last().unwrap().0 - repeated_state.0;
+        .map(|shape| rock_factory(chamber_width, shape))
+        .collect::<Result<Vec<Rock>, Box<dyn Error>>>()?;
+    // the chamber is a vector of bitmask with the lower bits representing the chamber width
+    // 0 element is bottom and higher elements represent the heght
+    let mut chamber: Vec<u16> = Vec::new();
+    // add floor which is represented as 1111111
+    chamber.push((1 << chamber_width) - 1);
 
-    let iterations_before_cycle = repeated_state.0 - 1;
-    let height_before_cycle = *iteration_heights
-        .get(iterations_before_cycle as usize)
-        .unwrap();
+    // the state is composed of the reachable surface of the chamber, the rock that
+    // has fallen and the next jet id, for each state we store the rock index
+    let mut chamber_state_history: HashMap<(Vec<u16>, usize, usize), u64> = HashMap::new();
+    let mut heights: Vec<u64> = Vec::new();
+    let mut jet_id = 0;
+    // number of lines removed from the bottom of the chamber
+    let mut pruned_lines: u64 = 0;
+    let mut max_chamber_length = chamber.len();
+
+    for iteration in 0.. {
+        let rock_id = (iteration % rocks.len() as u64) as usize;
+        let mut falling_rock = rocks[rock_id].clone();
+        jet_id = drop_rock(&mut chamber, &mut falling_rock, jets, jet_id, chamber_width);
+        heights.push(pruned_lines + chamber.len() as u64 - 1);
+        max_chamber_length = max_chamber_length.max(chamber.len());
+
+        let surface = reachable_surface(&chamber, chamber_width);
+        if surface.len() < MAX_SURFACE_DEPTH {
+            // the line under the surface is sealed since no rock can reach it, hence it
+            // becomes the new floor and the lines below can be removed
+            let sealed_line = chamber.len() - 1 - surface.len();
+            chamber.drain(..sealed_line);
+            chamber[0] = (1 << chamber_width) - 1;
+            pruned_lines += sealed_line as u64;
+        }
 
-    let cycle_relative_height = iteration_heights.last().unwrap()
-        - iteration_heights.get(repeated_state.0 as usize).unwrap();
+        let state = (surface, rock_id, jet_id);
+        if let Some(&cycle_start) = chamber_state_history.get(&state) {
+            return Ok(TowerCycle {
+                heights,
+                cycle_start,
+                cycle_length: iteration - cycle_start,
+                max_chamber_length,
+            });
+        }
+        chamber_state_history.insert(state, iteration);
+    }
+    unreachable!("the rocks fall until a state repeats")
+}
 
-    let remaining_iterations = max_iterations - iterations_before_cycle;
-    let complete_repetitions = remaining_iterations / cycle_length;
+/// returns the height of the tower after the given number of rocks have fallen
+/// in a chamber of the given width
+pub fn tower_height(jets: &[i8], rocks: u64, chamber_width: u8) -> Result<u64, Box<dyn Error>> {
+    tower_height_with_rocks(jets, &default_shapes(), rocks, chamber_width)
+}
 
-    let cycle_total_height = complete_repetitions * cycle_relative_height as i128;
+/// returns the height of the tower after count rocks have fallen in a chamber
+/// of the given width, the rocks cycle through the given shapes
+pub fn tower_height_with_rocks(
+    jets: &[i8],
+    shapes: &[RockShape],
+    count: u64,
+    chamber_width: u8,
+) -> Result<u64, Box<dyn Error>> {
+    let cycle = find_cycle(jets, shapes, chamber_width)?;
+    debug!(
+        "found a cycle of {} rocks after {} rocks, the chamber was at most {} lines long",
+        cycle.cycle_length, cycle.cycle_start, cycle.max_chamber_length
+    );
+    Ok(cycle.height(count))
+}
 
-    let iterations_after_cycle = remaining_iterations % cycle_length;
+/// detects the cycle of the tower once and returns a function that
+/// computes the height after any number of rocks
+///
+/// panics if there are no jets
+pub fn make_height_oracle(jets: &[i8]) -> impl Fn(u64) -> u64 {
+    let cycle = find_cycle(jets, &default_shapes(), DEFAULT_CHAMBER_WIDTH)
+        .expect("the jets are not empty and the default rocks fit in the default chamber");
+    move |count| cycle.height(count)
+}
+
+/// returns the minimum number of rocks that have to fall for the tower
+/// to be at least target_height high
+///
+/// panics if there are no jets
+pub fn rocks_for_height(jets: &[i8], target_height: u64) -> u64 {
+    find_cycle(jets, &default_shapes(), DEFAULT_CHAMBER_WIDTH)
+        .expect("the jets are not empty and the default rocks fit in the default chamber")
+        .rocks_for(target_height)
+}
 
-    let partial_cycle_height = iteration_heights
-        .get(repeated_state.0 as usize + iterations_after_cycle as usize)
-        .unwrap()
-        - iteration_heights.get(repeated_state.0 as usize).unwrap();
+fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let jets = parse_input(puzzle_input)?;
+    Ok(Answer::from(tower_height(
+        &jets,
+        2022,
+        DEFAULT_CHAMBER_WIDTH,
+    )?))
+}
 
-    let total_height =
-        height_before_cycle as i128 + cycle_total_height + partial_cycle_height as i128;
-    // soluzione giusta è 1562536022966 quindi si conta + 1 per qualche motivo
-    Ok(total_height.to_string())
+fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+    let jets = parse_input(puzzle_input)?;
+    Ok(Answer::from(tower_height(
+        &jets,
+        1000000000000,
+        DEFAULT_CHAMBER_WIDTH,
+    )?))
 }
 
 #[cfg(test)]
 mod test {
     use std::{error::Error, fs::File, io::Read};
 
-    use super::{solve_pt1, solve_pt2};
+    use crate::Answer;
+
+    use super::{
+        default_shapes, drop_rock, find_cycle, make_height_oracle, parse_input, parse_jets,
+        reachable_surface, render_chamber, rock_factory, rocks_for_height, solve_pt1, solve_pt2,
+        surface_profile, tower_height, tower_height_with_rocks, RockShape,
+    };
 
     #[test]
     fn test_pt1() -> Result<(), Box<dyn Error>> {
@@ -427,7 +510,7 @@ mod test {
         file.read_to_string(&mut puzzle_input)?;
         let result = solve_pt1(puzzle_input)?;
 
-        assert_eq!("3068".to_string(), result);
+        assert_eq!(Answer::Int(3068), result);
 
         Ok(())
     }
@@ -440,7 +523,7 @@ mod test {
 
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!("1514285714288".to_string(), result);
+        assert_eq!(Answer::Int(1514285714288), result);
 
         Ok(())
     }
@@ -453,7 +536,286 @@ mod test {
 
         let result = solve_pt2(puzzle_input)?;
 
-        assert_eq!("1562536022966".to_string(), result);
+        assert_eq!(Answer::Int(1562536022966), result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tower_height() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_17_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let jets = parse_input(puzzle_input)?;
+
+        assert_eq!(3068, tower_height(&jets, 2022, 7)?);
+        assert_eq!(15148, tower_height(&jets, 10000, 7)?);
+        assert_eq!(1, tower_height(&jets, 1, 7)?);
+        assert_eq!(0, tower_height(&jets, 0, 7)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_rocks() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_17_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let jets = parse_input(puzzle_input)?;
+
+        assert_eq!(
+            3068,
+            tower_height_with_rocks(&jets, &default_shapes(), 2022, 7)?
+        );
+
+        // the jets push every cell against the left wall so they pile up
+        let cell = RockShape {
+            area: vec![1],
+            width: 1,
+        };
+        assert_eq!(
+            2022,
+            tower_height_with_rocks(&[-1], std::slice::from_ref(&cell), 2022, 7)?
+        );
+        assert_eq!(
+            1000000000000,
+            tower_height_with_rocks(&[-1], &[cell], 1000000000000, 7)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_chamber() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_17_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let jets = parse_input(puzzle_input)?;
+
+        let mut chamber: Vec<u16> = vec![127];
+        let shapes = default_shapes();
+        let jet_id = drop_rock(&mut chamber, &mut rock_factory(7, &shapes[0])?, &jets, 0, 7);
+        assert_eq!("..####.\n", render_chamber(&chamber, 7));
+
+        drop_rock(
+            &mut chamber,
+            &mut rock_factory(7, &shapes[1])?,
+            &jets,
+            jet_id,
+            7,
+        );
+        let rendered = render_chamber(&chamber, 7);
+        assert_eq!(Some("...#..."), rendered.lines().next());
+        assert_eq!("...#...\n..###..\n...#...\n..####.\n", rendered);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_surface_profile() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_17_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let jets = parse_input(puzzle_input)?;
+
+        let mut chamber: Vec<u16> = vec![127];
+        assert_eq!(vec![0; 7], surface_profile(&chamber, 7));
+
+        let shapes = default_shapes();
+        let jet_id = drop_rock(&mut chamber, &mut rock_factory(7, &shapes[0])?, &jets, 0, 7);
+        drop_rock(
+            &mut chamber,
+            &mut rock_factory(7, &shapes[1])?,
+            &jets,
+            jet_id,
+            7,
+        );
+        // ...#...
+        // ..###..
+        // ...#...
+        // ..####.
+        assert_eq!(vec![0, 0, 3, 4, 3, 1, 0], surface_profile(&chamber, 7));
+
+        // the gaps under an overhang do not matter
+        let chamber = vec![127, 0b0001000, 0b1111100, 0b0000000];
+        assert_eq!(vec![2, 2, 2, 2, 2, 0, 0], surface_profile(&chamber, 7));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_height_oracle() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_17_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let oracle = make_height_oracle(&parse_input(puzzle_input)?);
+
+        assert_eq!(3068, oracle(2022));
+        assert_eq!(1514285714288, oracle(1000000000000));
+        assert_eq!(15148, oracle(10000));
+        assert_eq!(0, oracle(0));
+
+        let mut file = File::open("inputs/day_17.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let oracle = make_height_oracle(&parse_input(puzzle_input)?);
+
+        assert_eq!(3065, oracle(2022));
+        assert_eq!(1562536022966, oracle(1000000000000));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rocks_for_height() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_17_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let jets = parse_input(puzzle_input)?;
+
+        assert_eq!(0, rocks_for_height(&jets, 0));
+        assert_eq!(1, rocks_for_height(&jets, 1));
+        assert_eq!(2022, rocks_for_height(&jets, 3068));
+
+        for target in [2, 4, 100, 3067, 3069, 15148, 1514285714288, 1514285714289] {
+            let count = rocks_for_height(&jets, target);
+
+            assert!(tower_height(&jets, count, 7)? >= target, "{target}");
+            assert!(tower_height(&jets, count - 1, 7)? < target, "{target}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_input() -> Result<(), Box<dyn Error>> {
+        assert_eq!(vec![-1, 1], parse_input("<>\n".to_string())?);
+        assert_eq!(vec![1, 1, -1], parse_input(" >>\r\n<\n".to_string())?);
+        assert!(parse_input("<>x\n".to_string()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_jets() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_17_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let jets = parse_input(puzzle_input.clone())?;
+        let renamed = puzzle_input.replace('<', "L").replace('>', "R");
+
+        assert_eq!(jets, parse_jets(&renamed, 'L', 'R')?);
+        assert_eq!(
+            3068,
+            tower_height(&parse_jets(&renamed, 'L', 'R')?, 2022, 7)?
+        );
+        assert_eq!(vec![1, -1, 1], parse_jets("LRL\n", 'R', 'L')?);
+
+        assert!(parse_jets(&renamed, '<', '>').is_err());
+        assert!(parse_jets("LRX", 'L', 'R').is_err());
+        assert!(parse_jets("LL", 'L', 'L').is_err());
+        assert!(parse_jets("", '<', '>').is_err());
+        assert!(parse_jets(" \n", '<', '>').is_err());
+        assert!(solve_pt1("\n".to_string()).is_err());
+        assert!(tower_height(&[], 10, 7).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reachable_surface() {
+        // the top line is reachable from above, the one below only through the gap
+        // on the right and from there the gap moves left under the overhang
+        let chamber = vec![127, 0b0001000, 0b1111100, 0b0000000];
+        assert_eq!(
+            vec![127, 0b0000011, 0b0000111],
+            reachable_surface(&chamber, 7)
+        );
+        assert_eq!(Vec::<u16>::new(), reachable_surface(&[127], 7));
+    }
+
+    #[test]
+    fn test_deep_surface() -> Result<(), Box<dyn Error>> {
+        // the tall rocks hide a gap that the next rocks reach only later, with
+        // a fingerprint of the top 10 lines the height was 2799
+        let shapes = [
+            RockShape {
+                area: vec![12, 13, 4, 10, 2],
+                width: 4,
+            },
+            RockShape {
+                area: vec![1],
+                width: 3,
+            },
+            RockShape {
+                area: vec![1; 11],
+                width: 1,
+            },
+        ];
+        let jets = [1, 1, -1, 1, 1, 1, -1, 1, 1, -1, 1];
+        assert_eq!(2766, tower_height_with_rocks(&jets, &shapes, 600, 7)?);
+        assert_eq!(23051, tower_height_with_rocks(&jets, &shapes, 5000, 7)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_chamber_width() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_17_example.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let jets = parse_input(puzzle_input)?;
+        let minus = RockShape {
+            area: vec![15],
+            width: 4,
+        };
+
+        // in the default chamber the rocks pile up while with width 9 two of them fit side by side
+        assert_eq!(
+            2022,
+            tower_height_with_rocks(&jets, std::slice::from_ref(&minus), 2022, 7)?
+        );
+        assert_eq!(
+            2020,
+            tower_height_with_rocks(&jets, std::slice::from_ref(&minus), 2022, 9)?
+        );
+        assert_eq!(2633, tower_height(&jets, 2022, 9)?);
+
+        assert!(tower_height_with_rocks(&jets, std::slice::from_ref(&minus), 10, 5).is_err());
+        assert!(tower_height(&jets, 10, 16).is_err());
+        let wrong_shape = RockShape {
+            area: vec![15],
+            width: 2,
+        };
+        assert!(tower_height_with_rocks(&jets, &[wrong_shape], 10, 7).is_err());
+
+        // without rocks or with empty rocks the tower cannot grow
+        assert!(tower_height_with_rocks(&jets, &[], 10, 7).is_err());
+        for area in [vec![], vec![0], vec![0, 0]] {
+            let empty_shape = RockShape { area, width: 1 };
+            assert!(tower_height_with_rocks(&jets, &[empty_shape], 10, 7).is_err());
+        }
+        let empty_shape = RockShape {
+            area: vec![0],
+            width: 0,
+        };
+        assert!(tower_height_with_rocks(&jets, &[empty_shape], 10, 7).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_chamber_pruning() -> Result<(), Box<dyn Error>> {
+        let mut file = File::open("inputs/day_17.txt")?;
+        let mut puzzle_input = String::new();
+        file.read_to_string(&mut puzzle_input)?;
+        let jets = parse_input(puzzle_input)?;
+        let cycle = find_cycle(&jets, &default_shapes(), 7)?;
+
+        // the tower is thousands of lines high before the cycle is found
+        assert!(cycle.heights.last().unwrap() > &4000);
+        assert!(cycle.max_chamber_length < 100);
+        assert_eq!(3065, cycle.height(2022));
 
         Ok(())
     }
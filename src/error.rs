@@ -0,0 +1,69 @@
+use std::{error::Error, fmt::Display, io};
+
+/// Error of the solvers, parsing errors point to the line of the puzzle input
+#[derive(Debug)]
+pub enum AocError {
+    /// line is 1-based, like in editors
+    Parse {
+        day: u8,
+        line: usize,
+        msg: String,
+    },
+    Io(io::Error),
+}
+
+impl AocError {
+    pub fn parse(day: u8, line: usize, msg: impl Into<String>) -> Self {
+        AocError::Parse {
+            day,
+            line,
+            msg: msg.into(),
+        }
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocError::Parse { day, line, msg } => {
+                write!(f, "day {day}: cannot parse line {line}: {msg}")
+            }
+            AocError::Io(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Parse { .. } => None,
+            AocError::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for AocError {
+    fn from(value: io::Error) -> Self {
+        AocError::Io(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io;
+
+    use super::AocError;
+
+    #[test]
+    fn test_display() {
+        let error = AocError::parse(5, 3, "missing the destination stack");
+        assert_eq!(
+            "day 5: cannot parse line 3: missing the destination stack",
+            error.to_string()
+        );
+
+        let error = AocError::from(io::Error::new(io::ErrorKind::NotFound, "no input"));
+        assert!(matches!(error, AocError::Io(_)));
+        assert_eq!("no input", error.to_string());
+    }
+}
@@ -1,33 +1,66 @@
+#[macro_use]
+mod macros;
+
 pub mod day_0;
+#[cfg(feature = "day-01")]
 pub mod day_01;
+#[cfg(feature = "day-02")]
 pub mod day_02;
+#[cfg(feature = "day-03")]
 pub mod day_03;
+#[cfg(feature = "day-04")]
 pub mod day_04;
+#[cfg(feature = "day-05")]
 pub mod day_05;
+#[cfg(feature = "day-06")]
 pub mod day_06;
+#[cfg(feature = "day-07")]
 pub mod day_07;
+#[cfg(feature = "day-08")]
 pub mod day_08;
+#[cfg(feature = "day-09")]
 pub mod day_09;
+#[cfg(feature = "day-10")]
 pub mod day_10;
+#[cfg(feature = "day-11")]
 pub mod day_11;
+#[cfg(feature = "day-12")]
 pub mod day_12;
+#[cfg(feature = "day-13")]
 pub mod day_13;
+#[cfg(feature = "day-14")]
 pub mod day_14;
+#[cfg(feature = "day-15")]
 pub mod day_15;
+#[cfg(feature = "day-16")]
 pub mod day_16;
+#[cfg(feature = "day-17")]
 pub mod day_17;
+#[cfg(feature = "day-18")]
 pub mod day_18;
+#[cfg(feature = "day-19")]
 pub mod day_19;
+#[cfg(feature = "day-20")]
 pub mod day_20;
+#[cfg(feature = "day-21")]
 pub mod day_21;
+#[cfg(feature = "day-22")]
 pub mod day_22;
+#[cfg(feature = "day-23")]
 pub mod day_23;
+#[cfg(feature = "day-24")]
 pub mod day_24;
+#[cfg(feature = "day-25")]
 pub mod day_25;
+pub mod error;
+pub mod runner;
+pub mod utils;
+
+use std::{fmt::Display, str::FromStr};
 
 use clap::Parser;
 use clap_verbosity_flag::{InfoLevel, Verbosity};
-use strum_macros::{Display, EnumString};
+use strum_macros::Display;
 
 /// Arguments to pass to cli application
 #[derive(Parser, Debug)]
@@ -53,10 +86,128 @@ pub struct CliArgs {
     pub verbose: Verbosity<InfoLevel>,
 }
 
-#[derive(EnumString, Display, Clone, Debug)]
+#[derive(Display, Clone, Debug, PartialEq, Eq)]
 pub enum ProblemPart {
-    #[strum(ascii_case_insensitive)]
     One,
-    #[strum(ascii_case_insensitive)]
     Two,
+    /// part one followed by part two
+    Both,
+}
+
+impl FromStr for ProblemPart {
+    type Err = String;
+
+    /// accepts the number of the part, its name or the pt prefix, like 1, one or pt1
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "1" | "one" | "pt1" => Ok(ProblemPart::One),
+            "2" | "two" | "pt2" => Ok(ProblemPart::Two),
+            "both" => Ok(ProblemPart::Both),
+            _ => Err(format!(
+                "invalid part '{s}', expected one of 1, one, pt1, 2, two, pt2, both"
+            )),
+        }
+    }
+}
+
+pub const USAGE: &str = "usage: aoc <day> <part>, for instance aoc 16 2";
+
+/// parses the short form of the arguments, the day followed by the part,
+/// the error contains the usage of the application
+pub fn parse_day_and_part(args: &[String]) -> Result<(u8, ProblemPart), String> {
+    let [day, part] = args else {
+        return Err(USAGE.to_string());
+    };
+    let day = day
+        .parse::<u8>()
+        .ok()
+        .filter(|day| (1..=25).contains(day))
+        .ok_or_else(|| {
+            format!("invalid day '{day}', expected a number between 1 and 25\n{USAGE}")
+        })?;
+    let part = part
+        .parse::<ProblemPart>()
+        .map_err(|error| format!("{error}\n{USAGE}"))?;
+    Ok((day, part))
+}
+
+/// Solution of a problem part, numeric for most of the days
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+    /// answers of part one and part two
+    Both(Box<Answer>, Box<Answer>),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{value}"),
+            Answer::Text(value) => write!(f, "{value}"),
+            Answer::Both(first, second) => write!(f, "{first}, {second}"),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Int(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i32, i64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_day_and_part, ProblemPart};
+
+    #[test]
+    fn test_problem_part_from_str() {
+        for part in ["1", "one", "One", "ONE", "pt1", "PT1"] {
+            assert_eq!(Ok(ProblemPart::One), part.parse());
+        }
+        for part in ["2", "two", "Two", "pt2"] {
+            assert_eq!(Ok(ProblemPart::Two), part.parse());
+        }
+        assert_eq!(Ok(ProblemPart::Both), "both".parse());
+        for part in ["", "3", "three", "pt", "part1"] {
+            assert!(part.parse::<ProblemPart>().is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_day_and_part() {
+        let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<String>>();
+
+        assert_eq!(
+            Ok((16, ProblemPart::Two)),
+            parse_day_and_part(&args(&["16", "2"]))
+        );
+        assert_eq!(
+            Ok((1, ProblemPart::One)),
+            parse_day_and_part(&args(&["1", "pt1"]))
+        );
+        assert!(parse_day_and_part(&args(&["16"]))
+            .unwrap_err()
+            .starts_with("usage"));
+        assert!(parse_day_and_part(&args(&["16", "2", "3"])).is_err());
+        assert!(parse_day_and_part(&args(&["26", "1"])).is_err());
+        assert!(parse_day_and_part(&args(&["x", "1"])).is_err());
+        assert!(parse_day_and_part(&args(&["16", "3"]))
+            .unwrap_err()
+            .contains("usage"));
+    }
 }
@@ -0,0 +1,96 @@
+/// generates the solve function of a day, which loads the puzzle input from the file
+/// and logs the solution, and solve_str, which times and logs the given part functions.
+///
+/// The part functions take the puzzle input as String and return `Result<Answer, Box<dyn Error>>`
+///
+/// the macro is unused when all the days are disabled
+#[allow(unused_macros)]
+macro_rules! solve_day {
+    ($solve_pt1:ident, $solve_pt2:ident) => {
+        pub fn solve(
+            puzzle_input: &str,
+            part: $crate::ProblemPart,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            let puzzle_input = $crate::utils::load_input(puzzle_input)?;
+            let result = solve_str(&puzzle_input, part)?;
+            log::info!("Problem solution is {}", result);
+            Ok(())
+        }
+
+        /// solves the part of the problem on the puzzle input given as a string
+        pub fn solve_str(
+            puzzle_input: &str,
+            part: $crate::ProblemPart,
+        ) -> Result<$crate::Answer, Box<dyn std::error::Error>> {
+            let puzzle_input = puzzle_input.to_string();
+
+            let result = match part {
+                $crate::ProblemPart::One => {
+                    log::info!("Start solving part 1");
+                    let start = std::time::Instant::now();
+                    let result = $solve_pt1(puzzle_input)?;
+                    let duration = start.elapsed().as_millis();
+                    log::info!("Solved part 1 in {duration} milli seconds.");
+                    result
+                }
+                $crate::ProblemPart::Two => {
+                    log::info!("Start solving part 2");
+                    let start = std::time::Instant::now();
+                    let result = $solve_pt2(puzzle_input)?;
+                    let duration = start.elapsed().as_millis();
+                    log::info!("Solved part 2 in {duration} milli seconds.");
+                    result
+                }
+                $crate::ProblemPart::Both => $crate::Answer::Both(
+                    Box::new(solve_str(&puzzle_input, $crate::ProblemPart::One)?),
+                    Box::new(solve_str(&puzzle_input, $crate::ProblemPart::Two)?),
+                ),
+            };
+            Ok(result)
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    mod day {
+        use std::error::Error;
+
+        use crate::Answer;
+
+        solve_day!(solve_pt1, solve_pt2);
+
+        fn solve_pt1(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+            Ok(Answer::from(puzzle_input.lines().count()))
+        }
+
+        fn solve_pt2(puzzle_input: String) -> Result<Answer, Box<dyn Error>> {
+            Ok(Answer::Text(puzzle_input.lines().rev().collect()))
+        }
+    }
+
+    use crate::{Answer, ProblemPart};
+
+    #[test]
+    fn test_solve_day() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            Answer::Int(3),
+            day::solve_str("a\nb\nc\n", ProblemPart::One)?
+        );
+        assert_eq!(
+            Answer::Text("cba".to_string()),
+            day::solve_str("a\nb\nc\n", ProblemPart::Two)?
+        );
+        assert_eq!(
+            Answer::Both(
+                Box::new(Answer::Int(3)),
+                Box::new(Answer::Text("cba".to_string()))
+            ),
+            day::solve_str("a\nb\nc\n", ProblemPart::Both)?
+        );
+
+        assert!(day::solve("inputs/day_01_example.txt", ProblemPart::One).is_ok());
+        assert!(day::solve("inputs/missing_day.txt", ProblemPart::One).is_err());
+        Ok(())
+    }
+}
@@ -1,10 +1,29 @@
+use std::{env, process};
+
 use advent_of_code_template::*;
 use clap::Parser;
+use clap_verbosity_flag::Verbosity;
 use log::error;
 
 fn main() {
-    // Parse arguments
-    let args = CliArgs::parse();
+    // Parse arguments, the short form is the day followed by the part
+    let raw_args: Vec<String> = env::args().skip(1).collect();
+    let args = if raw_args.first().is_some_and(|arg| !arg.starts_with('-')) {
+        match parse_day_and_part(&raw_args) {
+            Ok((day, part)) => CliArgs {
+                day,
+                part,
+                puzzle_input: None,
+                verbose: Verbosity::new(0, 0),
+            },
+            Err(usage) => {
+                eprintln!("{usage}");
+                process::exit(2);
+            }
+        }
+    } else {
+        CliArgs::parse()
+    };
 
     // Set logger
     env_logger::Builder::new()
@@ -18,156 +37,182 @@ fn main() {
                 .unwrap_or(String::from("inputs/day_0.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-01")]
         1 => day_01::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_01.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-02")]
         2 => day_02::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_02.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-03")]
         3 => day_03::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_03.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-04")]
         4 => day_04::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_04.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-05")]
         5 => day_05::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_05.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-06")]
         6 => day_06::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_06.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-07")]
         7 => day_07::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_07.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-08")]
         8 => day_08::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_08.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-09")]
         9 => day_09::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_09.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-10")]
         10 => day_10::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_10.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-11")]
         11 => day_11::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_11.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-12")]
         12 => day_12::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_12.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-13")]
         13 => day_13::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_13.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-14")]
         14 => day_14::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_14.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-15")]
         15 => day_15::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_15.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-16")]
         16 => day_16::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_16.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-17")]
         17 => day_17::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_17.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-18")]
         18 => day_18::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_18.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-19")]
         19 => day_19::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_19.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-20")]
         20 => day_20::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_20.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-21")]
         21 => day_21::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_21.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-22")]
         22 => day_22::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_22.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-23")]
         23 => day_23::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_23.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-24")]
         24 => day_24::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_24.txt")),
             args.part,
         ),
+        #[cfg(feature = "day-25")]
         25 => day_25::solve(
             &args
                 .puzzle_input
                 .unwrap_or(String::from("inputs/day_25.txt")),
             args.part,
         ),
+        day if (1..=25).contains(&day) => Err(runner::disabled_day(day)),
         _ => {
             error!("Ops, you submitted a wrong day! Retry a number between 0 and 25 ");
             Ok(())
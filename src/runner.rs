@@ -0,0 +1,304 @@
+use std::{error::Error, ops::RangeInclusive, time::Duration, time::Instant};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{utils::load_input, Answer, ProblemPart};
+
+/// solves the part of the given day on the puzzle input given as a string
+pub fn solve_day(day: u8, puzzle_input: &str, part: ProblemPart) -> Result<Answer, Box<dyn Error>> {
+    match day {
+        #[cfg(feature = "day-01")]
+        1 => crate::day_01::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-02")]
+        2 => crate::day_02::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-03")]
+        3 => crate::day_03::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-04")]
+        4 => crate::day_04::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-05")]
+        5 => crate::day_05::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-06")]
+        6 => crate::day_06::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-07")]
+        7 => crate::day_07::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-08")]
+        8 => crate::day_08::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-09")]
+        9 => crate::day_09::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-10")]
+        10 => crate::day_10::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-11")]
+        11 => crate::day_11::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-12")]
+        12 => crate::day_12::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-13")]
+        13 => crate::day_13::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-14")]
+        14 => crate::day_14::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-15")]
+        15 => crate::day_15::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-16")]
+        16 => crate::day_16::solve_str(puzzle_input, part),
+        #[cfg(feature = "day-17")]
+        17 => crate::day_17::solve_str(puzzle_input, part),
+        day if (1..=17).contains(&day) => {
+            // with every day disabled the arguments are used only here
+            let _ = (puzzle_input, part);
+            Err(disabled_day(day))
+        }
+        _ => Err(format!("day {day} is not solved yet").into()),
+    }
+}
+
+/// error of a day whose module is not compiled since its feature is disabled
+pub fn disabled_day(day: u8) -> Box<dyn Error> {
+    format!("day {day} is disabled, build with the day-{day:02} feature").into()
+}
+
+/// Outcome of a part of a day executed by run_all
+#[derive(Debug)]
+pub enum RunOutcome {
+    Solved {
+        answer: Answer,
+        elapsed: Duration,
+    },
+    Failed(String),
+    /// the puzzle input could not be loaded
+    Skipped(String),
+}
+
+#[derive(Debug)]
+pub struct DayRun {
+    pub day: u8,
+    pub part: ProblemPart,
+    pub outcome: RunOutcome,
+}
+
+/// solves both parts of the day, when the puzzle input is missing both parts are skipped
+fn run_day(day: u8, input_path: &str) -> Vec<DayRun> {
+    let puzzle_input = load_input(input_path);
+    [ProblemPart::One, ProblemPart::Two]
+        .into_iter()
+        .map(|part| {
+            let outcome = match &puzzle_input {
+                Ok(puzzle_input) => {
+                    let start = Instant::now();
+                    match solve_day(day, puzzle_input, part.clone()) {
+                        Ok(answer) => RunOutcome::Solved {
+                            answer,
+                            elapsed: start.elapsed(),
+                        },
+                        Err(error) => RunOutcome::Failed(error.to_string()),
+                    }
+                }
+                Err(error) => RunOutcome::Skipped(error.to_string()),
+            };
+            DayRun { day, part, outcome }
+        })
+        .collect()
+}
+
+/// solves both parts of the given days, the puzzle input of each day is read
+/// from the path returned by input_path and when it is missing the day is skipped
+pub fn run_all(days: RangeInclusive<u8>, input_path: impl Fn(u8) -> String) -> Vec<DayRun> {
+    days.flat_map(|day| run_day(day, &input_path(day)))
+        .collect()
+}
+
+/// like run_all but the days are solved concurrently, since they are independent.
+///
+/// The runs are sorted by day and part as in run_all
+pub fn run_all_parallel(
+    days: RangeInclusive<u8>,
+    input_path: impl Fn(u8) -> String + Sync,
+) -> Vec<DayRun> {
+    let mut runs: Vec<DayRun> = days
+        .into_par_iter()
+        .flat_map_iter(|day| run_day(day, &input_path(day)))
+        .collect();
+    runs.sort_by_key(|run| (run.day, matches!(run.part, ProblemPart::Two)));
+    runs
+}
+
+/// Answer of a solved part as serialized by runs_to_json
+#[derive(Serialize, Debug)]
+pub struct JsonAnswer {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub millis: u128,
+}
+
+/// serializes the solved parts as a JSON array, skipped and failed parts are left out
+pub fn runs_to_json(runs: &[DayRun]) -> Result<String, serde_json::Error> {
+    let answers: Vec<JsonAnswer> = runs
+        .iter()
+        .filter_map(|run| match &run.outcome {
+            RunOutcome::Solved { answer, elapsed } => Some(JsonAnswer {
+                day: run.day,
+                part: match run.part {
+                    ProblemPart::One => 1,
+                    ProblemPart::Two => 2,
+                    ProblemPart::Both => 0,
+                },
+                answer: answer.to_string(),
+                millis: elapsed.as_millis(),
+            }),
+            _ => None,
+        })
+        .collect();
+    serde_json::to_string_pretty(&answers)
+}
+
+/// Expected answer of a part, the answers file is a JSON array of them
+/// like the one printed by runs_to_json
+#[derive(Deserialize, Debug)]
+pub struct ExpectedAnswer {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+}
+
+/// reads the expected answers from a JSON file
+pub fn load_expected_answers(path: &str) -> Result<Vec<ExpectedAnswer>, Box<dyn Error>> {
+    let answers = serde_json::from_str(&load_input(path)?)
+        .map_err(|error| format!("cannot parse {path}: {error}"))?;
+    Ok(answers)
+}
+
+/// solves the part of the day and compares the answer with the expected one
+pub fn verify(day: u8, part: ProblemPart, expected: &str, input: &str) -> Result<(), String> {
+    let answer = solve_day(day, input, part.clone())
+        .map_err(|error| format!("day {day} part {part}: {error}"))?;
+    if answer.to_string() == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "day {day} part {part}: expected {expected} but the answer is {answer}"
+        ))
+    }
+}
+
+/// verifies each expected answer against the puzzle input of its day, which is read
+/// from the path returned by input_path, and returns the errors of the wrong ones
+pub fn verify_all(expected: &[ExpectedAnswer], input_path: impl Fn(u8) -> String) -> Vec<String> {
+    let mut errors: Vec<String> = Vec::new();
+    for expected_answer in expected {
+        let day = expected_answer.day;
+        let part = match expected_answer.part {
+            1 => ProblemPart::One,
+            2 => ProblemPart::Two,
+            part => {
+                errors.push(format!("day {day}: invalid part {part}"));
+                continue;
+            }
+        };
+        let result = load_input(&input_path(day))
+            .map_err(|error| format!("day {day} part {part}: {error}"))
+            .and_then(|input| verify(day, part, &expected_answer.answer, &input));
+        if let Err(error) = result {
+            errors.push(error);
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{Answer, ProblemPart};
+
+    use super::{runs_to_json, solve_day, DayRun, RunOutcome};
+    #[cfg(feature = "day-01")]
+    use super::{verify, verify_all, ExpectedAnswer};
+
+    #[cfg(feature = "day-01")]
+    #[test]
+    fn test_enabled_day() -> Result<(), Box<dyn std::error::Error>> {
+        let result = solve_day(1, "1000\n2000\n\n3000\n\n", ProblemPart::One)?;
+        assert_eq!(Answer::Int(3000), result);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "day-02"))]
+    #[test]
+    fn test_disabled_day() {
+        let error = solve_day(2, "A Y\n", ProblemPart::One).unwrap_err();
+        assert!(error.to_string().contains("day-02 feature"));
+    }
+
+    #[test]
+    fn test_unknown_day() {
+        assert!(solve_day(18, "", ProblemPart::One).is_err());
+    }
+
+    #[test]
+    fn test_runs_to_json() -> Result<(), Box<dyn std::error::Error>> {
+        let runs = vec![
+            DayRun {
+                day: 1,
+                part: ProblemPart::One,
+                outcome: RunOutcome::Solved {
+                    answer: Answer::Int(24000),
+                    elapsed: Duration::from_micros(1500),
+                },
+            },
+            DayRun {
+                day: 1,
+                part: ProblemPart::Two,
+                outcome: RunOutcome::Failed("no elves".to_string()),
+            },
+            DayRun {
+                day: 2,
+                part: ProblemPart::One,
+                outcome: RunOutcome::Skipped("missing input".to_string()),
+            },
+        ];
+        let json: serde_json::Value = serde_json::from_str(&runs_to_json(&runs)?)?;
+
+        assert_eq!(
+            serde_json::json!([{ "day": 1, "part": 1, "answer": "24000", "millis": 1 }]),
+            json
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "day-01")]
+    #[test]
+    fn test_verify() {
+        let puzzle_input = "1000\n2000\n\n3000\n\n";
+
+        assert_eq!(Ok(()), verify(1, ProblemPart::One, "3000", puzzle_input));
+        let error = verify(1, ProblemPart::One, "2999", puzzle_input).unwrap_err();
+        assert!(error.contains("expected 2999"));
+    }
+
+    #[cfg(feature = "day-01")]
+    #[test]
+    fn test_verify_all() {
+        let expected = vec![
+            ExpectedAnswer {
+                day: 1,
+                part: 1,
+                answer: "24000".to_string(),
+            },
+            ExpectedAnswer {
+                day: 1,
+                part: 2,
+                answer: "1".to_string(),
+            },
+            ExpectedAnswer {
+                day: 1,
+                part: 3,
+                answer: "1".to_string(),
+            },
+        ];
+        let errors = verify_all(&expected, |day| format!("inputs/day_{day:02}_example.txt"));
+
+        assert_eq!(2, errors.len());
+        assert!(errors[0].contains("expected 1 but the answer is 45000"));
+        assert!(errors[1].contains("invalid part 3"));
+    }
+}
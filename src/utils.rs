@@ -0,0 +1,77 @@
+use std::{error::Error, fs, fs::File, io::Read, path::Path};
+
+/// reads the whole puzzle input file, the error contains the path of the file
+pub fn load_input(path: &str) -> Result<String, Box<dyn Error>> {
+    let mut file = File::open(path).map_err(|error| format!("cannot open {path}: {error}"))?;
+    let mut puzzle_input = String::new();
+    file.read_to_string(&mut puzzle_input)
+        .map_err(|error| format!("cannot read {path}: {error}"))?;
+    Ok(puzzle_input)
+}
+
+/// returns the puzzle input of the day from inputs/day_XX.txt, when the file is missing
+/// it is downloaded with the session cookie of adventofcode.com and saved there
+pub fn fetch_input(day: u8, session: &str) -> Result<String, Box<dyn Error>> {
+    fetch_input_cached(day, session, &format!("inputs/day_{day:02}.txt"))
+}
+
+fn fetch_input_cached(day: u8, session: &str, path: &str) -> Result<String, Box<dyn Error>> {
+    if Path::new(path).exists() {
+        return load_input(path);
+    }
+    let puzzle_input = download_input(day, session)?;
+    fs::write(path, &puzzle_input).map_err(|error| format!("cannot write {path}: {error}"))?;
+    Ok(puzzle_input)
+}
+
+#[cfg(feature = "download")]
+fn download_input(day: u8, session: &str) -> Result<String, Box<dyn Error>> {
+    let url = format!("https://adventofcode.com/2022/day/{day}/input");
+    let puzzle_input = ureq::get(&url)
+        .set("Cookie", &format!("session={session}"))
+        .set(
+            "User-Agent",
+            "github.com/alelavelli/advent-of-code-2022 by alelavelli",
+        )
+        .call()
+        .map_err(|error| format!("cannot download {url}: {error}"))?
+        .into_string()?;
+    Ok(puzzle_input)
+}
+
+#[cfg(not(feature = "download"))]
+fn download_input(day: u8, _session: &str) -> Result<String, Box<dyn Error>> {
+    Err(
+        format!("the input of day {day} is missing, build with the download feature to fetch it")
+            .into(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use super::{fetch_input_cached, load_input};
+
+    #[test]
+    fn test_load_input() {
+        assert!(load_input("inputs/day_01_example.txt").is_ok());
+
+        let error = load_input("inputs/missing_day.txt").unwrap_err();
+        assert!(error.to_string().contains("inputs/missing_day.txt"));
+    }
+
+    #[test]
+    fn test_fetch_input_cached() -> Result<(), Box<dyn std::error::Error>> {
+        let path = env::temp_dir().join("aoc_2022_fetch_input_cached.txt");
+        let path = path.to_str().unwrap();
+        fs::write(path, "cached input\n")?;
+
+        // the file exists so the invalid session is never used
+        let puzzle_input = fetch_input_cached(1, "invalid session", path)?;
+        fs::remove_file(path)?;
+
+        assert_eq!("cached input\n", puzzle_input);
+        Ok(())
+    }
+}
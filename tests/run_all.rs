@@ -0,0 +1,87 @@
+#![cfg(feature = "all-days")]
+
+use advent_of_code_template::{
+    runner::{run_all, run_all_parallel, RunOutcome},
+    Answer, ProblemPart,
+};
+
+/// answers of the example inputs, days 6 and 15 are missing since their examples
+/// use different lines or parameters from the actual puzzle
+fn example_answer(day: u8, part: &ProblemPart) -> Option<Answer> {
+    let (one, two) = match day {
+        1 => (Answer::Int(24000), Answer::Int(45000)),
+        2 => (Answer::Int(15), Answer::Int(12)),
+        3 => (Answer::Int(157), Answer::Int(70)),
+        4 => (Answer::Int(2), Answer::Int(4)),
+        5 => (
+            Answer::Text("CMZ".to_string()),
+            Answer::Text("MCD".to_string()),
+        ),
+        7 => (Answer::Int(95437), Answer::Int(24933642)),
+        8 => (Answer::Int(21), Answer::Int(8)),
+        9 => (Answer::Int(13), Answer::Int(1)),
+        11 => (Answer::Int(10605), Answer::Int(2713310158)),
+        12 => (Answer::Int(31), Answer::Int(29)),
+        13 => (Answer::Int(13), Answer::Int(140)),
+        14 => (Answer::Int(24), Answer::Int(93)),
+        16 => (Answer::Int(1651), Answer::Int(1707)),
+        17 => (Answer::Int(3068), Answer::Int(1514285714288)),
+        _ => return None,
+    };
+    match part {
+        ProblemPart::One => Some(one),
+        ProblemPart::Two => Some(two),
+        ProblemPart::Both => Some(Answer::Both(Box::new(one), Box::new(two))),
+    }
+}
+
+#[test]
+fn test_run_all_examples() {
+    let runs = run_all(1..=17, |day| format!("inputs/day_{day:02}_example.txt"));
+    assert_eq!(34, runs.len());
+
+    for run in runs {
+        let RunOutcome::Solved { answer, .. } = run.outcome else {
+            panic!("day {} part {} was not solved", run.day, run.part);
+        };
+        if let Some(expected) = example_answer(run.day, &run.part) {
+            assert_eq!(expected, answer, "day {} part {}", run.day, run.part);
+        }
+    }
+}
+
+#[test]
+fn test_run_all_missing_input() {
+    let runs = run_all(1..=2, |day| format!("inputs/missing_day_{day:02}.txt"));
+
+    assert_eq!(4, runs.len());
+    assert!(runs.iter().all(
+        |run| matches!(&run.outcome, RunOutcome::Skipped(reason) if reason.contains("missing_day"))
+    ));
+}
+
+#[test]
+fn test_run_all_parallel() {
+    let input_path = |day: u8| format!("inputs/day_{day:02}_example.txt");
+    let serial = run_all(1..=17, input_path);
+    let parallel = run_all_parallel(1..=17, input_path);
+
+    assert_eq!(serial.len(), parallel.len());
+    for (serial_run, parallel_run) in serial.iter().zip(parallel.iter()) {
+        assert_eq!(serial_run.day, parallel_run.day);
+        assert_eq!(serial_run.part.to_string(), parallel_run.part.to_string());
+        match (&serial_run.outcome, &parallel_run.outcome) {
+            (
+                RunOutcome::Solved { answer, .. },
+                RunOutcome::Solved {
+                    answer: parallel_answer,
+                    ..
+                },
+            ) => assert_eq!(answer, parallel_answer),
+            _ => panic!(
+                "day {} part {} was not solved",
+                serial_run.day, serial_run.part
+            ),
+        }
+    }
+}